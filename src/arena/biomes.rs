@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use noise::{NoiseFn, Perlin};
+use crate::arena::generation::{Asteroid, MapAddress};
+use crate::components::{DamageEvent, DeathEvent, Health, Resistances};
+use crate::components::blast::Blast;
+use crate::components::cargo::Ore;
+use crate::effects::ExplosionEvent;
+
+// Cells per unit of biome noise, low so regions span many chunks
+const BIOME_FREQUENCY: f64 = 0.015;
+const VOLATILE_RADIUS_PER_SCALE: f32 = 6.0;
+const VOLATILE_DAMAGE_PER_SCALE: f32 = 15.0;
+const VOLATILE_IMPULSE_PER_SCALE: f32 = 8.0;
+pub const JAGGED_DAMAGE_MULTIPLIER: f32 = 2.0;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Biome {
+    DeepSpace,
+    RockField,
+    IceBelt,
+    MetallicCluster
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hazard {
+    // Explodes when destroyed, damaging anything nearby
+    Volatile,
+    // Sharp edges that hurt more to collide with
+    Jagged
+}
+
+#[derive(Component)]
+pub struct Hazardous(pub Hazard);
+
+pub struct BiomeParams {
+    pub name: &'static str,
+    // Worley value a cell needs to hold an asteroid, higher is sparser
+    pub cutoff: f64,
+    pub min_scale: f32,
+    pub max_scale: f32,
+    pub toughness: f32,
+    pub resistances: Resistances,
+    // Mass per unit of collider volume
+    pub density: f32,
+    // Share of asteroids that spawn as dynamic bodies and drift
+    pub drift_chance: f32,
+    pub color: Color,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub hazards: &'static [(Hazard, f32)],
+    pub ores: &'static [(Ore, f32)]
+}
+
+impl Biome {
+    pub const ALL: [Biome; 4] = [Biome::DeepSpace, Biome::RockField, Biome::IceBelt, Biome::MetallicCluster];
+
+    pub fn params(&self) -> BiomeParams {
+        match self {
+            Biome::DeepSpace => BiomeParams {
+                name: "Deep Space",
+                cutoff: 0.9,
+                min_scale: 1.5,
+                max_scale: 5.0,
+                toughness: 1.0,
+                resistances: Resistances::NONE,
+                density: 1.0,
+                drift_chance: 0.5,
+                color: Color::rgb(0.4, 0.38, 0.36),
+                metallic: 0.0,
+                roughness: 0.9,
+                hazards: &[(Hazard::Volatile, 0.1)],
+                ores: &[(Ore::Iron, 0.1), (Ore::Crystal, 0.05)]
+            },
+            Biome::RockField => BiomeParams {
+                name: "Rock Field",
+                cutoff: 0.7,
+                min_scale: 0.5,
+                max_scale: 4.0,
+                toughness: 1.0,
+                resistances: Resistances::NONE,
+                density: 1.0,
+                drift_chance: 0.2,
+                color: Color::rgb(0.8, 0.7, 0.6),
+                metallic: 0.0,
+                roughness: 0.8,
+                hazards: &[(Hazard::Volatile, 0.03)],
+                ores: &[(Ore::Iron, 0.1)]
+            },
+            Biome::IceBelt => BiomeParams {
+                name: "Ice Belt",
+                cutoff: 0.55,
+                min_scale: 0.3,
+                max_scale: 1.5,
+                toughness: 0.6,
+                resistances: Resistances {
+                    thermal: 2.0,
+                    ..Resistances::NONE
+                },
+                density: 0.5,
+                drift_chance: 0.6,
+                color: Color::rgb(0.75, 0.9, 1.0),
+                metallic: 0.0,
+                roughness: 0.2,
+                hazards: &[],
+                ores: &[(Ore::Ice, 0.3)]
+            },
+            Biome::MetallicCluster => BiomeParams {
+                name: "Metallic Cluster",
+                cutoff: 0.65,
+                min_scale: 0.8,
+                max_scale: 2.5,
+                toughness: 2.0,
+                resistances: Resistances {
+                    kinetic: 0.3,
+                    explosive: 1.5,
+                    ..Resistances::NONE
+                },
+                density: 3.0,
+                drift_chance: 0.1,
+                color: Color::rgb(0.6, 0.6, 0.65),
+                metallic: 0.9,
+                roughness: 0.3,
+                hazards: &[(Hazard::Jagged, 0.2), (Hazard::Volatile, 0.05)],
+                ores: &[(Ore::Iron, 0.3), (Ore::Crystal, 0.05)]
+            }
+        }
+    }
+
+    pub fn roll_hazard(&self, roll: f32) -> Option<Hazard> {
+        roll_chances(self.params().hazards, roll)
+    }
+
+    pub fn roll_ore(&self, roll: f32) -> Option<Ore> {
+        roll_chances(self.params().ores, roll)
+    }
+}
+
+// Picks at most one entry from a table of chances using a roll in 0..1
+fn roll_chances<T: Copy>(chances: &[(T, f32)], roll: f32) -> Option<T> {
+    let mut threshold = 0.0;
+    for (entry, chance) in chances {
+        threshold += chance;
+        if roll < threshold {
+            return Some(*entry);
+        }
+    }
+    None
+}
+
+// Low frequency noise layered over the Worley field to split the arena into regions
+#[derive(Resource, Clone, Copy)]
+pub struct BiomeMap(Perlin);
+
+impl BiomeMap {
+    pub fn new(seed: u32) -> Self {
+        Self(Perlin::new(seed.wrapping_add(1)))
+    }
+
+    pub fn biome_at(&self, cell: &MapAddress) -> Biome {
+        let value = self.0.get([
+            cell.x as f64 * BIOME_FREQUENCY,
+            cell.y as f64 * BIOME_FREQUENCY,
+            cell.z as f64 * BIOME_FREQUENCY
+        ]);
+        match value {
+            value if value < -0.3 => Biome::DeepSpace,
+            value if value < 0.15 => Biome::RockField,
+            value if value < 0.4 => Biome::IceBelt,
+            _ => Biome::MetallicCluster
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct BiomeMaterials {
+    biomes: HashMap<Biome, Handle<StandardMaterial>>,
+    volatile: Handle<StandardMaterial>
+}
+
+impl BiomeMaterials {
+    pub fn get(&self, biome: &Biome, hazard: Option<Hazard>) -> Handle<StandardMaterial> {
+        match hazard {
+            Some(Hazard::Volatile) => self.volatile.clone(),
+            _ => self.biomes[biome].clone()
+        }
+    }
+}
+
+pub fn biome_setup(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+){
+    let biomes = Biome::ALL.iter()
+        .map(|biome| {
+            let params = biome.params();
+            (*biome, materials.add(StandardMaterial {
+                base_color: params.color,
+                metallic: params.metallic,
+                perceptual_roughness: params.roughness,
+                ..default()
+            }))
+        })
+        .collect();
+    commands.spawn(BiomeMaterials {
+        biomes,
+        volatile: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.5, 0.2, 0.1),
+            emissive: Color::rgb(0.8, 0.25, 0.0),
+            ..default()
+        })
+    });
+}
+
+pub fn detonate_volatile_asteroids(
+    mut death_event: EventReader<DeathEvent>,
+    volatile: Query<(&Transform, &Asteroid, &Hazardous)>,
+    targets: Query<(Entity, &GlobalTransform), With<Health>>,
+    mut damage_event: EventWriter<DamageEvent>,
+    mut explosion_event: EventWriter<ExplosionEvent>
+){
+    for death in death_event.iter() {
+        let Ok((transform, asteroid, Hazardous(Hazard::Volatile))) = volatile.get(death.subject) else {
+            continue;
+        };
+        explosion_event.send(ExplosionEvent {
+            position: transform.translation,
+            power: asteroid.scale * 2.0
+        });
+        let blast = Blast {
+            position: transform.translation,
+            radius: asteroid.scale * VOLATILE_RADIUS_PER_SCALE,
+            damage: asteroid.scale * VOLATILE_DAMAGE_PER_SCALE,
+            impulse: asteroid.scale * VOLATILE_IMPULSE_PER_SCALE
+        };
+        let targets = targets.iter()
+            .filter(|(target, _)| *target != death.subject)
+            .map(|(target, target_transform)| (target, target_transform.translation()));
+        damage_event.send_batch(blast.damage_events(death.subject, targets));
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use crate::arena::generation::{MapAddress, SpawnArea};
+
+// Cells along each side of a chunk
+pub const CHUNK_SIZE: i32 = 4;
+// Chunks stay loaded this far past the load radius, so flying along a border does not thrash
+pub const UNLOAD_HYSTERESIS: i32 = 1;
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChunkAddress {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32
+}
+
+impl ChunkAddress {
+    pub fn from_cell(cell: &MapAddress) -> Self {
+        Self {
+            x: cell.x.div_euclid(CHUNK_SIZE),
+            y: cell.y.div_euclid(CHUNK_SIZE),
+            z: cell.z.div_euclid(CHUNK_SIZE)
+        }
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = MapAddress> + '_ {
+        (0..(CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE)).map(move |n| MapAddress {
+            x: self.x * CHUNK_SIZE + (n % CHUNK_SIZE),
+            y: self.y * CHUNK_SIZE + ((n / CHUNK_SIZE) % CHUNK_SIZE),
+            z: self.z * CHUNK_SIZE + (n / (CHUNK_SIZE * CHUNK_SIZE))
+        })
+    }
+
+    // Chebyshev distance, matching the cube shaped spawn area
+    pub fn distance(&self, other: &ChunkAddress) -> i32 {
+        (self.x - other.x).abs()
+            .max((self.y - other.y).abs())
+            .max((self.z - other.z).abs())
+    }
+
+    pub fn within(&self, radius: i32) -> impl Iterator<Item = ChunkAddress> + '_ {
+        let size = radius * 2 + 1;
+        (0..(size * size * size)).map(move |n| ChunkAddress {
+            x: self.x - radius + (n % size),
+            y: self.y - radius + ((n / size) % size),
+            z: self.z - radius + (n / (size * size))
+        })
+    }
+}
+
+// Spawn area radius rounded up to whole chunks
+pub fn chunk_radius(area: &SpawnArea) -> i32 {
+    (area.radius + CHUNK_SIZE - 1) / CHUNK_SIZE
+}
+
+// Chunk an asteroid is currently filed under, which drifts away from its home cell's chunk
+#[derive(Component, Clone, Copy)]
+pub struct ChunkMember(pub ChunkAddress);
+
+#[derive(Default)]
+pub struct Chunk {
+    pub entities: Vec<Entity>
+}
+
+// Loaded chunks and the asteroids that belong to each
+#[derive(Resource, Default)]
+pub struct ChunkMap {
+    chunks: HashMap<ChunkAddress, Chunk>,
+    // Home cells whose asteroid is still alive in another chunk, so reloading home must not spawn a copy
+    wandering: HashSet<MapAddress>
+}
+
+impl ChunkMap {
+    pub fn contains(&self, chunk: &ChunkAddress) -> bool {
+        self.chunks.contains_key(chunk)
+    }
+
+    pub fn load(&mut self, chunk: ChunkAddress) {
+        self.chunks.entry(chunk).or_default();
+    }
+
+    pub fn unload(&mut self, chunk: &ChunkAddress) -> Option<Chunk> {
+        self.chunks.remove(chunk)
+    }
+
+    pub fn loaded(&self) -> impl Iterator<Item = &ChunkAddress> {
+        self.chunks.keys()
+    }
+
+    pub fn add_entity(&mut self, chunk: &ChunkAddress, entity: Entity) -> bool {
+        match self.chunks.get_mut(chunk) {
+            Some(loaded) => {
+                loaded.entities.push(entity);
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn remove_entity(&mut self, chunk: &ChunkAddress, entity: Entity) {
+        if let Some(loaded) = self.chunks.get_mut(chunk) {
+            loaded.entities.retain(|member| *member != entity);
+        }
+    }
+
+    pub fn move_entity(&mut self, from: &ChunkAddress, to: &ChunkAddress, entity: Entity) -> bool {
+        self.remove_entity(from, entity);
+        self.add_entity(to, entity)
+    }
+
+    pub fn is_wandering(&self, home: &MapAddress) -> bool {
+        self.wandering.contains(home)
+    }
+
+    pub fn set_wandering(&mut self, home: &MapAddress, wandering: bool) {
+        if wandering {
+            self.wandering.insert(home.clone());
+        } else {
+            self.wandering.remove(home);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.wandering.clear();
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use crate::arena::fragments::Fragment;
+use crate::arena::generation::{Asteroid, MapAddress};
+use crate::arena::seed::WorldSeed;
+use crate::components::Health;
+
+const SAVE_DIR: &str = "saves";
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellDelta {
+    Destroyed,
+    Damaged { health: f32 }
+}
+
+// Changes the player made to the generated field, replayed on top of the noise
+#[derive(Resource, Default)]
+pub struct WorldDelta {
+    cells: HashMap<MapAddress, CellDelta>
+}
+
+impl WorldDelta {
+    pub fn get(&self, address: &MapAddress) -> Option<CellDelta> {
+        self.cells.get(address).copied()
+    }
+
+    pub fn record_destroyed(&mut self, address: &MapAddress) {
+        self.cells.insert(address.clone(), CellDelta::Destroyed);
+    }
+
+    pub fn record_damaged(&mut self, address: &MapAddress, health: f32) {
+        if self.get(address) != Some(CellDelta::Destroyed) {
+            self.cells.insert(address.clone(), CellDelta::Damaged { health });
+        }
+    }
+
+    fn to_save_string(&self) -> String {
+        self.cells.iter()
+            .map(|(address, delta)| match delta {
+                CellDelta::Destroyed => format!("{0} {1} {2} destroyed\n", address.x, address.y, address.z),
+                CellDelta::Damaged { health } => format!("{0} {1} {2} damaged {3}\n", address.x, address.y, address.z, health)
+            })
+            .collect()
+    }
+
+    fn from_save_string(save: &str) -> Self {
+        let cells = save.lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let address = MapAddress {
+                    x: fields.first()?.parse().ok()?,
+                    y: fields.get(1)?.parse().ok()?,
+                    z: fields.get(2)?.parse().ok()?
+                };
+                let delta = match *fields.get(3)? {
+                    "destroyed" => CellDelta::Destroyed,
+                    "damaged" => CellDelta::Damaged { health: fields.get(4)?.parse().ok()? },
+                    _ => return None
+                };
+                Some((address, delta))
+            })
+            .collect();
+        Self { cells }
+    }
+}
+
+fn save_path(seed: &WorldSeed) -> PathBuf {
+    PathBuf::from(SAVE_DIR).join(format!("world-{0}.delta", seed.0))
+}
+
+pub fn load_world_delta(
+    mut commands: Commands,
+    seed: Res<WorldSeed>
+){
+    let delta = fs::read_to_string(save_path(&seed))
+        .map(|save| WorldDelta::from_save_string(&save))
+        .unwrap_or_default();
+    commands.insert_resource(delta);
+}
+
+// Damage on asteroids that are still loaded has not been recorded yet
+fn record_loaded_damage(
+    delta: &mut WorldDelta,
+    asteroids: &Query<(&Asteroid, &Health), Without<Fragment>>
+){
+    for (asteroid, health) in asteroids.iter() {
+        if health.current < health.full {
+            delta.record_damaged(&asteroid.address, health.current);
+        }
+    }
+}
+
+fn write_world_delta(delta: &WorldDelta, seed: &WorldSeed) {
+    let result = fs::create_dir_all(SAVE_DIR)
+        .and_then(|_| fs::write(save_path(seed), delta.to_save_string()));
+    if let Err(error) = result {
+        println!("Error saving world delta: {0}", error);
+    }
+}
+
+pub fn save_world_delta(
+    mut delta: ResMut<WorldDelta>,
+    seed: Res<WorldSeed>,
+    asteroids: Query<(&Asteroid, &Health), Without<Fragment>>
+){
+    record_loaded_damage(&mut delta, &asteroids);
+    write_world_delta(&delta, &seed);
+}
+
+pub fn save_world_delta_on_exit(
+    mut app_exit: EventReader<AppExit>,
+    mut delta: ResMut<WorldDelta>,
+    seed: Res<WorldSeed>,
+    asteroids: Query<(&Asteroid, &Health), Without<Fragment>>
+){
+    if app_exit.iter().next().is_some() {
+        record_loaded_damage(&mut delta, &asteroids);
+        write_world_delta(&delta, &seed);
+    }
+}
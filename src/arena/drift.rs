@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use bevy::ecs::query::Has;
+use bevy_xpbd_3d::prelude::*;
+use crate::arena::chunks::{ChunkAddress, ChunkMap, ChunkMember};
+use crate::arena::delta::WorldDelta;
+use crate::arena::fragments::Fragment;
+use crate::arena::generation::{translation_to_address, Asteroid};
+use crate::components::Health;
+use crate::effects::ExplosionEvent;
+use crate::util::random_direction;
+
+const MAX_DRIFT_SPEED: f32 = 1.5;
+const MAX_DRIFT_SPIN: f32 = 0.5;
+// Seeds the starting velocity and spin of a drifting asteroid
+const DRIFT_VELOCITY_SALT: u64 = 0x3C3C_C3C3_9696_6969;
+const EXPLOSION_PUSH_RADIUS_PER_POWER: f32 = 4.0;
+const EXPLOSION_IMPULSE_PER_POWER: f32 = 5.0;
+
+
+// Starting linear and angular velocity for a drifting asteroid, the same every time its cell loads
+pub fn drift_velocity(asteroid_seed: u64) -> (Vec3, Vec3) {
+    let mut rng = fastrand::Rng::with_seed(asteroid_seed ^ DRIFT_VELOCITY_SALT);
+    let linear = random_direction(&mut rng) * MAX_DRIFT_SPEED * rng.f32();
+    let angular = random_direction(&mut rng) * MAX_DRIFT_SPIN * rng.f32();
+    (linear, angular)
+}
+
+type DriftingAsteroidQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a Position, &'a Asteroid, &'a Health, &'a mut ChunkMember, Has<Fragment>),
+    Changed<Position>
+>;
+
+// Re-files asteroids that drifted into another chunk, and drops those that left the loaded area
+pub fn track_drifting_asteroids(
+    mut commands: Commands,
+    mut chunk_map: ResMut<ChunkMap>,
+    mut world_delta: ResMut<WorldDelta>,
+    mut asteroids: DriftingAsteroidQuery
+){
+    for (entity, position, asteroid, health, mut member, is_fragment) in &mut asteroids {
+        let chunk = ChunkAddress::from_cell(&translation_to_address(position.0));
+        if chunk == member.0 {
+            continue;
+        }
+
+        if chunk_map.move_entity(&member.0, &chunk, entity) {
+            member.0 = chunk;
+            if !is_fragment {
+                let home = ChunkAddress::from_cell(&asteroid.address);
+                chunk_map.set_wandering(&asteroid.address, chunk != home);
+            }
+            continue;
+        }
+
+        // Drifted past the loaded area, it comes back at its home cell when that reloads
+        if !is_fragment {
+            if health.current < health.full {
+                world_delta.record_damaged(&asteroid.address, health.current);
+            }
+            chunk_map.set_wandering(&asteroid.address, false);
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn push_from_explosions(
+    mut explosion_event: EventReader<ExplosionEvent>,
+    mut bodies: Query<(&Position, &Mass, &RigidBody, &mut LinearVelocity), With<Asteroid>>
+){
+    for explosion in explosion_event.iter() {
+        let radius = explosion.power * EXPLOSION_PUSH_RADIUS_PER_POWER;
+        for (position, mass, rigid_body, mut velocity) in &mut bodies {
+            let offset = position.0 - explosion.position;
+            let distance = offset.length();
+            if !rigid_body.is_dynamic() || distance > radius || distance <= f32::EPSILON || mass.0 <= f32::EPSILON {
+                continue;
+            }
+            let impulse = explosion.power * EXPLOSION_IMPULSE_PER_POWER * (1.0 - distance / radius);
+            velocity.0 += offset / distance * impulse / mass.0;
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use crate::arena::generation::Asteroid;
+use crate::util::random_direction;
+
+const FRAGMENT_MIN_PARENT_SCALE: f32 = 1.5;
+const FRAGMENT_MIN_SCALE: f32 = 0.4;
+const MIN_FRAGMENTS: usize = 2;
+const MAX_FRAGMENTS: usize = 4;
+// Share of the parent's volume that survives as fragments, the rest is dust
+const FRAGMENT_VOLUME_RETAINED: f32 = 0.7;
+const FRAGMENT_VELOCITY_INHERITED: f32 = 0.8;
+const FRAGMENT_SCATTER_SPEED: f32 = 3.0;
+const FRAGMENT_SPIN: f32 = 1.5;
+
+
+#[derive(Component)]
+pub struct Fragment;
+
+pub struct FragmentData {
+    pub asteroid: Asteroid,
+    pub offset: Vec3,
+    pub linear_velocity: Vec3,
+    pub angular_velocity: Vec3
+}
+
+// Splits an asteroid into smaller pieces, seeded by the parent so the breakup is repeatable
+pub fn fragment_asteroid(parent: &Asteroid, parent_velocity: Vec3) -> Vec<FragmentData> {
+    if parent.scale < FRAGMENT_MIN_PARENT_SCALE {
+        return Vec::new();
+    }
+
+    let mut rng = fastrand::Rng::with_seed(parent.seed);
+    let count = rng.usize(MIN_FRAGMENTS..=MAX_FRAGMENTS);
+    let weights: Vec<f32> = (0..count).map(|_| 0.5 + rng.f32()).collect();
+    let total_weight: f32 = weights.iter().sum();
+
+    weights.iter()
+        .filter_map(|weight| {
+            let volume_share = weight / total_weight * FRAGMENT_VOLUME_RETAINED;
+            let scale = parent.scale * volume_share.cbrt();
+            let direction = random_direction(&mut rng);
+            let spin = random_direction(&mut rng) * FRAGMENT_SPIN * rng.f32();
+            let seed = rng.u64(..);
+            if scale < FRAGMENT_MIN_SCALE {
+                return None;
+            }
+            Some(FragmentData {
+                asteroid: Asteroid {
+                    address: parent.address.clone(),
+                    seed,
+                    scale,
+                    biome: parent.biome
+                },
+                offset: direction * parent.scale * 0.25,
+                linear_velocity: parent_velocity * FRAGMENT_VELOCITY_INHERITED
+                    + direction * FRAGMENT_SCATTER_SPEED,
+                angular_velocity: spin
+            })
+        })
+        .collect()
+}
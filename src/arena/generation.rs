@@ -1,32 +1,108 @@
+use std::cmp::Reverse;
 use bevy::prelude::*;
+use bevy::ecs::query::Has;
 use bevy_xpbd_3d::prelude::*;
 use noise::{
     core::worley::{distance_functions::*, worley_3d, ReturnType},
     permutationtable::PermutationTable
 };
-extern crate queues;
-use queues::*;
-use crate::components::{DamageEvent, DeathEvent, Health};
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+use itertools::Itertools;
+use crate::components::{DeathEvent, Health, PointValue, Resistances};
+use crate::components::cargo::Ore;
+use crate::components::faction::environment_layers;
 use crate::effects::{Explodeable, ExplosionEvent};
-use crate::player::Player;
-use crate::states::{GameStates, AppStates};
+use crate::arena::biomes::{Biome, BiomeMap, BiomeMaterials, BiomeParams, Hazard, Hazardous};
+use crate::arena::chunks::{ChunkAddress, ChunkMap, ChunkMember, chunk_radius, UNLOAD_HYSTERESIS};
+use crate::arena::drift::drift_velocity;
+use crate::arena::delta::{CellDelta, WorldDelta};
+use crate::arena::fragments::{Fragment, fragment_asteroid};
+use crate::arena::impact::ImpactVelocity;
+use crate::arena::ore::{OreDeposit, OreHandles};
+use crate::arena::rocks::{RockMeshCache, RockVariant};
+use crate::arena::seed::WorldSeed;
+use crate::util::config_value;
 
-const SPAWN_SEED:u32 = 69;
-const SPAWN_CUTOFF:f64 = 0.7;
+// World units per cell, shared by every spawner so their chunks line up
+pub const CELL_SCALE:i32 = 10;
+const ASTEROID_HEALTH_PER_AREA:f32 = 5.0;
+const ASTEROID_POINTS_PER_SCALE:f32 = 5.0;
+const DEFAULT_SPAWNS_PER_FRAME:usize = 32;
+const FRAGMENT_PUSH_PER_IMPULSE:f32 = 0.2;
+const MAX_FRAGMENT_PUSH:f32 = 4.0;
+const SPAWN_BUDGET_KEY: &str = "spawns_per_frame";
 
 
 #[derive(Resource)]
 pub struct SpawnHashTable(PermutationTable);
 
+// Salts so the hazard, drifting chance and ore rolls don't line up with the rock variant picked from the same hash
+const HAZARD_SALT:u64 = 0xA5A5_5A5A_0F0F_F0F0;
+const DRIFT_CHANCE_SALT:u64 = 0x5A5A_A5A5_F0F0_0F0F;
+const ORE_SALT:u64 = 0x0F0F_F0F0_A5A5_5A5A;
+
 
 #[derive(Component)]
-pub struct Asteroid;
+pub struct Asteroid {
+    pub address: MapAddress,
+    pub seed: u64,
+    pub scale: f32,
+    pub biome: Biome
+}
 
+#[derive(Bundle)]
+pub struct AsteroidBundle {
+    pbr: PbrBundle,
+    asteroid: Asteroid,
+    collider: Collider,
+    rigid_body: RigidBody,
+    mass: ColliderMassProperties,
+    impact_velocity: ImpactVelocity,
+    position: Position,
+    layers: CollisionLayers,
+    explodeable: Explodeable,
+    health: Health,
+    resistances: Resistances,
+    points: PointValue
+}
 
-#[derive(Component)]
-pub struct SpawnableHandles {
-    mesh: Handle<Mesh>,
-    material: Handle<StandardMaterial>
+impl AsteroidBundle {
+    // Mass follows from the collider volume and biome density, so larger rocks are heavier
+    pub fn new(
+        asteroid: Asteroid,
+        position: Vec3,
+        rigid_body: RigidBody,
+        rock: &RockVariant,
+        material: Handle<StandardMaterial>
+    ) -> Self {
+        let params = asteroid.biome.params();
+        let toughness = params.toughness;
+        let health = asteroid_health(asteroid.scale, toughness);
+        let collider = rock.collider(asteroid.scale);
+        Self {
+            pbr: PbrBundle {
+                mesh: rock.mesh.clone(),
+                material,
+                transform: Transform::from_scale(Vec3::splat(asteroid.scale)),
+                ..default()
+            },
+            mass: ColliderMassProperties::new_computed(&collider, params.density),
+            collider,
+            rigid_body,
+            impact_velocity: ImpactVelocity::default(),
+            position: Position(position),
+            layers: environment_layers(),
+            explodeable: Explodeable,
+            health: Health {
+                full: health,
+                current: health
+            },
+            resistances: params.resistances,
+            points: PointValue((asteroid.scale * toughness * ASTEROID_POINTS_PER_SCALE).round()),
+            asteroid
+        }
+    }
 }
 
 
@@ -34,222 +110,411 @@ pub struct SpawnableHandles {
 struct AsteroidData {
     address: MapAddress,
     size: i32,
-    scale: i32
+    health: Option<f32>,
+    biome: Biome,
+    hazard: Option<Hazard>,
+    ore: Option<Ore>,
+    drifting: bool
+}
+
+#[derive(Component)]
+pub struct SpawnArea {
+    pub radius: i32
 }
 
+// Chunk the spawner was in when it last loaded, None until its first update
+#[derive(Component, Default)]
+pub struct PreviousSpawnUpdate(pub Option<ChunkAddress>);
+
+// Anything carrying this keeps the arena loaded around it, the loaded region is the union of all of them
 #[derive(Bundle)]
 pub struct MapSpawnerBundle {
-    spawn_area: SpawnArea,
-    previous_spawn_update: PreviousSpawnUpdate,
-    spawn_queue: SpawnQueue
+    pub spawn_area: SpawnArea,
+    pub previous_spawn_update: PreviousSpawnUpdate
 }
 
-#[derive(Component)]
-pub struct SpawnArea {
-    pub radius: i32,
-    pub scale: i32
+impl MapSpawnerBundle {
+    pub fn new(radius: i32) -> Self {
+        Self {
+            spawn_area: SpawnArea { radius },
+            previous_spawn_update: PreviousSpawnUpdate::default()
+        }
+    }
 }
 
-#[derive(Component)]
-pub struct PreviousSpawnUpdate(pub MapAddress);
-
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct MapAddress {
     pub x: i32,
     pub y: i32,
     pub z: i32
 }
 
-#[derive(Component)]
-pub struct SpawnQueue(Queue<AsteroidData>);
+#[derive(Component, Default)]
+pub struct SpawnQueue(Vec<AsteroidData>);
+
+// Noise batches being evaluated off the main thread
+#[derive(Component, Default)]
+pub struct GenerationTasks(Vec<(ChunkAddress, Task<Vec<AsteroidData>>)>);
+
+// How many asteroids spawn_from_queue may create in one frame
+#[derive(Resource)]
+pub struct SpawnBudget {
+    pub per_frame: usize
+}
 
 pub fn spawn_setup(
+    mut commands: Commands
+){
+    commands.spawn((SpawnQueue::default(), GenerationTasks::default()));
+}
+
+pub fn setup_spawn_hasher(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    seed: Res<WorldSeed>
 ){
-    commands.insert_resource(SpawnHashTable{ 0: PermutationTable::new(SPAWN_SEED) });
-    commands.spawn(SpawnQueue {0: queue![]});
-    commands.spawn(SpawnableHandles {
-            mesh: meshes.add(Mesh::from(shape::Cube {size: 1.0})),
-            material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
-        });
+    commands.insert_resource(SpawnHashTable(PermutationTable::new(seed.0)));
+    commands.insert_resource(BiomeMap::new(seed.0));
 }
 
+pub fn load_spawn_budget(
+    mut commands: Commands
+){
+    let per_frame = config_value(SPAWN_BUDGET_KEY)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_SPAWNS_PER_FRAME);
+    commands.insert_resource(SpawnBudget { per_frame });
+}
+
+// Collects finished noise batches, dropping cells the player already cleared
+pub fn receive_generated_asteroids(
+    world_delta: Res<WorldDelta>,
+    spawners: Query<&GlobalTransform, With<SpawnArea>>,
+    mut query: Query<(&mut SpawnQueue, &mut GenerationTasks)>
+){
+    let (mut spawn_queue, mut tasks) = query.single_mut();
+    let queued = spawn_queue.0.len();
+    tasks.0.retain_mut(|(_, task)| {
+        let Some(generated) = future::block_on(future::poll_once(task)) else {
+            return true;
+        };
+        for mut spawnable in generated {
+            match world_delta.get(&spawnable.address) {
+                Some(CellDelta::Destroyed) => continue,
+                Some(CellDelta::Damaged { health }) => spawnable.health = Some(health),
+                None => {}
+            }
+            spawn_queue.0.push(spawnable);
+        }
+        false
+    });
+    if spawn_queue.0.len() == queued {
+        return;
+    }
+
+    // Furthest first, so the nearest cells are drained off the end.
+    // Distances are never negative, so their bit patterns sort the same way the floats do
+    spawn_queue.0.sort_by_cached_key(|spawnable| {
+        let position = address_to_translation(&spawnable.address);
+        let nearest = spawners.iter()
+            .map(|spawner| spawner.translation().distance_squared(position))
+            .fold(f32::MAX, f32::min);
+        Reverse(nearest.to_bits())
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_from_queue(
     mut commands: Commands,
-    mut spawn_queue_query: Query<&mut SpawnQueue, Changed<SpawnQueue>>,
-    handle_query: Query<&SpawnableHandles>
+    mut chunk_map: ResMut<ChunkMap>,
+    mut spawn_queue_query: Query<&mut SpawnQueue>,
+    budget: Res<SpawnBudget>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut rock_cache: ResMut<RockMeshCache>,
+    seed: Res<WorldSeed>,
+    materials_query: Query<&BiomeMaterials>,
+    ore_handle_query: Query<&OreHandles>
 ){
-    let handles = handle_query.single();
+    let materials = materials_query.single();
+    let ore_handles = ore_handle_query.single();
     for mut spawn_queue in &mut spawn_queue_query {
-        while spawn_queue.0.size() > 0 {
-            let spawnable_result = spawn_queue.0.remove();
-
-            match spawnable_result {
-                Ok(spawnable) => {
-                    let position = address_to_translation(spawnable.address, spawnable.scale);
-                    _= commands.spawn((PbrBundle {
-                        mesh: handles.mesh.clone(),
-                        material: handles.material.clone(),
-                        ..default()
-                    }, Asteroid,
-                        Collider::cuboid(1.0, 1.0, 1.0),
-                        RigidBody::Static,
-                        Position(position),
-                        Explodeable,
-                        Health {
-                            full: 10.0,
-                            current: 10.0
-                        }
-                    ))
-                },
-                Err(error) => println!("Error dequeing spawnnable: {0}", error)
+        if spawn_queue.0.is_empty() {
+            continue;
+        }
+
+        let spawn_count = budget.per_frame.min(spawn_queue.0.len());
+        let remaining = spawn_queue.0.len() - spawn_count;
+        for spawnable in spawn_queue.0.drain(remaining..) {
+            // Still alive elsewhere after drifting out of its home chunk
+            if chunk_map.is_wandering(&spawnable.address) {
+                continue;
+            }
+            let asteroid = Asteroid {
+                seed: seed.hash_address(&spawnable.address),
+                scale: asteroid_scale(spawnable.size, &spawnable.biome.params()),
+                address: spawnable.address,
+                biome: spawnable.biome
+            };
+            let rock = rock_cache.get_or_create(seed.0, asteroid.seed, &mut meshes);
+            let position = address_to_translation(&asteroid.address);
+            let (linear_velocity, angular_velocity) = drift_velocity(asteroid.seed);
+            let rigid_body = if spawnable.drifting { RigidBody::Dynamic } else { RigidBody::Static };
+            // Volatile rocks keep their warning glow even when they carry ore
+            let material = match spawnable.ore {
+                Some(ore) if spawnable.hazard != Some(Hazard::Volatile) => ore_handles.material(&ore),
+                _ => materials.get(&spawnable.biome, spawnable.hazard)
+            };
+            let mut bundle = AsteroidBundle::new(
+                asteroid,
+                position,
+                rigid_body,
+                rock,
+                material
+            );
+            if let Some(health) = spawnable.health {
+                bundle.health.current = health.min(bundle.health.full);
+            }
+            let chunk = ChunkAddress::from_cell(&bundle.asteroid.address);
+            let scale = bundle.asteroid.scale;
+            if chunk_map.contains(&chunk) {
+                let mut entity = commands.spawn((bundle, ChunkMember(chunk)));
+                if let Some(hazard) = spawnable.hazard {
+                    entity.insert(Hazardous(hazard));
+                }
+                if let Some(ore) = spawnable.ore {
+                    entity.insert(OreDeposit::new(ore, scale));
+                }
+                if spawnable.drifting {
+                    entity.insert((LinearVelocity(linear_velocity), AngularVelocity(angular_velocity)));
+                }
+                chunk_map.add_entity(&chunk, entity.id());
             }
         }
     }
 }
 
-fn translation_to_address(translation: Vec3, scale: i32) -> MapAddress {
+// Maps the noise derived size onto the biome's range from smallest pebble to largest rock
+fn asteroid_scale(size: i32, params: &BiomeParams) -> f32 {
+    let normalized = ((size as f64 / 1000.0 - params.cutoff) / (1.0 - params.cutoff)).clamp(0.0, 1.0) as f32;
+    params.min_scale + (params.max_scale - params.min_scale) * normalized
+}
+
+fn asteroid_health(scale: f32, toughness: f32) -> f32 {
+    (scale * scale * toughness * ASTEROID_HEALTH_PER_AREA).max(1.0)
+}
+
+pub fn translation_to_address(translation: Vec3) -> MapAddress {
     MapAddress {
-        x: (translation.x * (1.0 / scale as f32)).floor() as i32,
-        y: (translation.y * (1.0 / scale as f32)).floor() as i32,
-        z: (translation.z * (1.0 / scale as f32)).floor() as i32
+        x: (translation.x * (1.0 / CELL_SCALE as f32)).floor() as i32,
+        y: (translation.y * (1.0 / CELL_SCALE as f32)).floor() as i32,
+        z: (translation.z * (1.0 / CELL_SCALE as f32)).floor() as i32
     }
 }
 
-fn address_to_translation(address: MapAddress, scale: i32) -> Vec3 {
+fn address_to_translation(address: &MapAddress) -> Vec3 {
     Vec3::new(
-        (address.x * scale) as f32,
-        (address.y * scale) as f32,
-        (address.z * scale) as f32
+        (address.x * CELL_SCALE) as f32,
+        (address.y * CELL_SCALE) as f32,
+        (address.z * CELL_SCALE) as f32
     )
 }
 
+fn generate_asteroid(
+    hasher: &PermutationTable,
+    biomes: &BiomeMap,
+    seed: &WorldSeed,
+    address: MapAddress
+) -> Option<AsteroidData> {
+    let biome = biomes.biome_at(&address);
+    let noise_value = worley_3d(
+        hasher,
+        euclidean,
+        ReturnType::Value,
+        [address.x.into(), address.y.into(), address.z.into()]
+    );
+    if noise_value <= biome.params().cutoff {
+        return None;
+    }
+
+    let address_hash = seed.hash_address(&address);
+    let hazard_roll = fastrand::Rng::with_seed(address_hash ^ HAZARD_SALT).f32();
+    let drift_roll = fastrand::Rng::with_seed(address_hash ^ DRIFT_CHANCE_SALT).f32();
+    let ore_roll = fastrand::Rng::with_seed(address_hash ^ ORE_SALT).f32();
+    Some(AsteroidData {
+        address,
+        size: (noise_value * 1000.0) as i32,
+        health: None,
+        biome,
+        hazard: biome.roll_hazard(hazard_roll),
+        ore: biome.roll_ore(ore_roll),
+        drifting: drift_roll < biome.params().drift_chance
+    })
+}
+
+// Queues generation for every chunk that comes within range of a spawner.
+// Chunks already loaded or generating for an overlapping spawner are skipped.
 pub fn worley_spawner(
     spawn_hasher: Res<SpawnHashTable>,
-    mut query: Query<(&Transform, &SpawnArea, &mut PreviousSpawnUpdate)>,
-    mut spawn_queue_query: Query<&mut SpawnQueue>
+    biomes: Res<BiomeMap>,
+    seed: Res<WorldSeed>,
+    mut chunk_map: ResMut<ChunkMap>,
+    mut query: Query<(&GlobalTransform, &SpawnArea, &mut PreviousSpawnUpdate)>,
+    mut tasks_query: Query<&mut GenerationTasks>
 ){
-    let mut spawn_queue = spawn_queue_query.single_mut();
+    let task_pool = AsyncComputeTaskPool::get();
+    let mut tasks = tasks_query.single_mut();
     for(transform, area, mut previous) in &mut query
     {
-        let current_address = translation_to_address(transform.translation, area.scale);
-        if current_address == previous.0 {
+        let current_chunk = ChunkAddress::from_cell(&translation_to_address(transform.translation()));
+        if previous.0 == Some(current_chunk) {
             continue;
         }
-        let size = area.radius * 2;
-
-        for n in 0..(size * size * size) {
-
-            let position = MapAddress {
-                x: (current_address.x - area.radius + (n % size)),
-                y: (current_address.y - area.radius + ((n % (size * size)) / size)),
-                z: (current_address.z - area.radius + (n / (size * size)))
-            };
 
-            if intersecting(&position, &previous.0, &area.radius){
+        // Nearest chunks first, so results stream back closest to the spawner
+        let radius = chunk_radius(area);
+        for chunk in current_chunk.within(radius).sorted_by_key(|chunk| chunk.distance(&current_chunk)) {
+            if chunk_map.contains(&chunk) {
                 continue;
             }
+            chunk_map.load(chunk);
 
-            let noise_value = worley_3d(
-                &spawn_hasher.0,
-                &euclidean,
-                ReturnType::Value,
-                [position.x.into(), position.y.into(), position.z.into()]
-            );
-
-            if noise_value > SPAWN_CUTOFF {
-                let _ = spawn_queue.0.add(AsteroidData
-                    {
-                        address: MapAddress {
-                            x: position.x,
-                            y: position.y,
-                            z: position.z,
-                        },
-                        size: (noise_value * 1000.0) as i32,
-                        scale: area.scale
-                    }
-                );
-            }
+            let hasher = spawn_hasher.0;
+            let biomes = *biomes;
+            let seed = *seed;
+            tasks.0.push((chunk, task_pool.spawn(async move {
+                chunk.cells()
+                    .filter_map(|address| generate_asteroid(&hasher, &biomes, &seed, address))
+                    .collect()
+            })));
         }
-        previous.0 = current_address;
+        previous.0 = Some(current_chunk);
     }
 }
 
-pub fn intersecting(a_point: &MapAddress, b_bound: &MapAddress, radius: &i32) -> bool {
-    (a_point.x < b_bound.x + radius && a_point.x > b_bound.x - radius) &&
-        (a_point.y < b_bound.y + radius && a_point.y > b_bound.y - radius) &&
-        (a_point.z < b_bound.z + radius && a_point.z > b_bound.z - radius)
-}
-
-pub fn despawn_cubes(
+// Unloads chunks that are past the hysteresis margin of every spawner
+#[allow(clippy::too_many_arguments)]
+pub fn despawn_chunks(
     mut commands: Commands,
-    query: Query<(Entity, &Transform), With<Asteroid>>,
-    spawner: Query<(&Transform, &SpawnArea)>
+    mut chunk_map: ResMut<ChunkMap>,
+    mut world_delta: ResMut<WorldDelta>,
+    asteroids: Query<(&Asteroid, &Health, Has<Fragment>)>,
+    mut queues: Query<(&mut SpawnQueue, &mut GenerationTasks)>,
+    moved_spawners: Query<(), Changed<PreviousSpawnUpdate>>,
+    mut removed_spawners: RemovedComponents<SpawnArea>,
+    spawners: Query<(&PreviousSpawnUpdate, &SpawnArea)>
 ) {
-    for (entity, transform) in &query {
-        for (spawner_transform, spawner_area) in &spawner {
-            let entity_address = translation_to_address(transform.translation, spawner_area.scale);
-            let spawner_address = translation_to_address(spawner_transform.translation, spawner_area.scale);
-            if intersecting(&entity_address, &spawner_address, &spawner_area.radius) {
+    // Re-check when a spawner changes chunk or goes away, a chunk only unloads once no area wants it
+    let spawners_removed = removed_spawners.iter().count() > 0;
+    if moved_spawners.is_empty() && !spawners_removed {
+        return;
+    }
+
+    let unloaded: Vec<ChunkAddress> = chunk_map.loaded()
+        .filter(|chunk| spawners.iter().all(|(previous, area)| match &previous.0 {
+            Some(spawner_chunk) => chunk.distance(spawner_chunk) > chunk_radius(area) + UNLOAD_HYSTERESIS,
+            None => true
+        }))
+        .copied()
+        .collect();
+    if unloaded.is_empty() {
+        return;
+    }
+
+    for chunk in unloaded.iter() {
+        let Some(loaded) = chunk_map.unload(chunk) else {
+            continue;
+        };
+        for entity in loaded.entities {
+            let Ok((asteroid, health, is_fragment)) = asteroids.get(entity) else {
                 continue;
+            };
+            if !is_fragment {
+                if health.current < health.full {
+                    world_delta.record_damaged(&asteroid.address, health.current);
+                }
+                chunk_map.set_wandering(&asteroid.address, false);
             }
             commands.entity(entity).despawn_recursive();
         }
     }
+
+    // Drop any generation still pending for the unloaded chunks
+    let (mut spawn_queue, mut tasks) = queues.single_mut();
+    tasks.0.retain(|(chunk, _)| !unloaded.contains(chunk));
+    spawn_queue.0.retain(|spawnable| !unloaded.contains(&ChunkAddress::from_cell(&spawnable.address)));
 }
 
+type DestroyedAsteroidQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a Transform, &'a Asteroid, &'a ChunkMember, Option<&'a LinearVelocity>, Has<Fragment>)
+>;
+
+#[allow(clippy::too_many_arguments)]
 pub fn destroy_asteroids(
-    asteroids: Query<(Entity, &Transform), With<Asteroid>>,
+    asteroids: DestroyedAsteroidQuery,
     mut commands: Commands,
+    mut world_delta: ResMut<WorldDelta>,
+    mut chunk_map: ResMut<ChunkMap>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut rock_cache: ResMut<RockMeshCache>,
+    seed: Res<WorldSeed>,
+    materials_query: Query<&BiomeMaterials>,
     mut death_event: EventReader<DeathEvent>,
     mut explosion_event: EventWriter<ExplosionEvent>
 ){
+    let materials = materials_query.single();
     for death in death_event.iter(){
-        if let Ok((entity, transform)) = asteroids.get(death.subject) {
+        if let Ok((entity, transform, asteroid, member, velocity, is_fragment)) = asteroids.get(death.subject) {
+            if !is_fragment {
+                world_delta.record_destroyed(&asteroid.address);
+                chunk_map.set_wandering(&asteroid.address, false);
+            }
             explosion_event.send(ExplosionEvent {
                 position: transform.translation,
-                power: 1.0,
+                power: asteroid.scale,
             });
-            commands.entity(entity).despawn_recursive();
-        }
-    }
-}
-
-pub fn damage_player(
-    asteroids: Query<Entity, With<Asteroid>>,
-    players: Query<Entity, With<Player>>,
-    mut collision_event: EventReader<CollisionStarted>,
-    mut damage_event: EventWriter<DamageEvent>
-){
-    for collision in collision_event.iter() {
-        if let Ok(player) = players.get(collision.0) {
-            if asteroids.contains(collision.1) {
-                println!("Player First hit");
-                damage_event.send(DamageEvent{subject: player, source: collision.1, value: 10.0});
-            }
-        }
-        if let Ok(player) = players.get(collision.1) {
-            if asteroids.contains(collision.0) {
-                println!("Asteroid first hit");
-                damage_event.send(DamageEvent{subject: player, source: collision.0, value: 10.0});
+            let chunk = member.0;
+            chunk_map.remove_entity(&chunk, entity);
+            // Pieces carry on in the direction of the killing blow
+            let parent_velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.0)
+                + death.hit.map_or(Vec3::ZERO, |hit| hit.normal * (hit.impulse * FRAGMENT_PUSH_PER_IMPULSE).min(MAX_FRAGMENT_PUSH));
+            for fragment in fragment_asteroid(asteroid, parent_velocity) {
+                let rock = rock_cache.get_or_create(seed.0, fragment.asteroid.seed, &mut meshes);
+                let material = materials.get(&fragment.asteroid.biome, None);
+                let fragment_entity = commands.spawn((
+                    AsteroidBundle::new(
+                        fragment.asteroid,
+                        transform.translation + fragment.offset,
+                        RigidBody::Dynamic,
+                        rock,
+                        material
+                    ),
+                    LinearVelocity(fragment.linear_velocity),
+                    AngularVelocity(fragment.angular_velocity),
+                    ChunkMember(chunk),
+                    Fragment
+                )).id();
+                chunk_map.add_entity(&chunk, fragment_entity);
             }
+            commands.entity(entity).despawn_recursive();
         }
     }
 }
 
 pub fn clean_up_map(
     asteroids: Query<Entity, With<Asteroid>>,
-    mut queues: Query<&mut SpawnQueue>,
+    mut queues: Query<(&mut SpawnQueue, &mut GenerationTasks)>,
+    mut rock_cache: ResMut<RockMeshCache>,
+    mut chunk_map: ResMut<ChunkMap>,
     mut commands: Commands,
 ){
+    chunk_map.clear();
+    // Rock shapes depend on the seed, which can change before the next game
+    rock_cache.clear();
     for asteroid in asteroids.iter() {
         commands.entity(asteroid).despawn_recursive();
     }
-    for mut queue in &mut queues.iter_mut() {
-        while queue.0.size() > 0 {
-            let _ = queue.0.remove();
-        }
+    // Dropping the tasks cancels any generation still in flight
+    for (mut queue, mut tasks) in &mut queues {
+        queue.0.clear();
+        tasks.0.clear();
     }
 }
\ No newline at end of file
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::arena::biomes::{Hazard, Hazardous, JAGGED_DAMAGE_MULTIPLIER};
+use crate::arena::generation::Asteroid;
+use crate::components::{DamageEvent, DamageKind, HitInfo};
+use crate::player::Player;
+use crate::util::started_contacts;
+
+// Closing speed below which a bump does no damage
+const MIN_IMPACT_SPEED: f32 = 3.0;
+const DAMAGE_PER_IMPULSE: f32 = 1.0;
+
+
+// Velocity before this frame's physics step, the collision events are read after the solver already bounced the bodies apart
+#[derive(Component, Default)]
+pub struct ImpactVelocity(pub Vec3);
+
+pub fn record_impact_velocity(
+    mut bodies: Query<(&LinearVelocity, &mut ImpactVelocity)>
+){
+    for (velocity, mut impact) in &mut bodies {
+        impact.0 = velocity.0;
+    }
+}
+
+// Momentum exchanged along the contact normal, zero below the speed threshold
+fn impact_impulse(
+    contact: &Contact,
+    bodies: &Query<(&RigidBody, &InverseMass, Option<&ImpactVelocity>)>
+) -> f32 {
+    let Ok([(body1, inverse_mass1, velocity1), (body2, inverse_mass2, velocity2)]) = bodies.get_many([contact.entity1, contact.entity2]) else {
+        return 0.0;
+    };
+    let velocity = |velocity: Option<&ImpactVelocity>| velocity.map_or(Vec3::ZERO, |velocity| velocity.0);
+    let closing_speed = (velocity(velocity1) - velocity(velocity2)).dot(contact.normal).abs();
+    if closing_speed < MIN_IMPACT_SPEED {
+        return 0.0;
+    }
+
+    // Static bodies act as if infinitely heavy
+    let inverse_mass = |body: &RigidBody, inverse_mass: &InverseMass| if body.is_static() { 0.0 } else { inverse_mass.0 };
+    let total_inverse_mass = inverse_mass(body1, inverse_mass1) + inverse_mass(body2, inverse_mass2);
+    if total_inverse_mass <= f32::EPSILON {
+        return 0.0;
+    }
+    closing_speed / total_inverse_mass
+}
+
+// Ship and asteroid both take damage from how hard they met, not just that they touched
+pub fn impact_damage(
+    mut collision_started: EventReader<CollisionStarted>,
+    mut collisions: EventReader<Collision>,
+    bodies: Query<(&RigidBody, &InverseMass, Option<&ImpactVelocity>)>,
+    asteroids: Query<Option<&Hazardous>, With<Asteroid>>,
+    players: Query<(), With<Player>>,
+    mut damage_event: EventWriter<DamageEvent>
+){
+    for contact in started_contacts(&mut collision_started, &mut collisions) {
+        // The contact normal points from the first entity to the second
+        let (player, asteroid, player_point, asteroid_point, into_player) = if players.contains(contact.entity1) {
+            (contact.entity1, contact.entity2, contact.point1, contact.point2, -contact.normal)
+        } else {
+            (contact.entity2, contact.entity1, contact.point2, contact.point1, contact.normal)
+        };
+        let (true, Ok(hazard)) = (players.contains(player), asteroids.get(asteroid)) else {
+            continue;
+        };
+
+        let impulse = impact_impulse(&contact, &bodies);
+        let damage = impulse * DAMAGE_PER_IMPULSE;
+        if damage <= 0.0 {
+            continue;
+        }
+        let multiplier = match hazard {
+            Some(Hazardous(Hazard::Jagged)) => JAGGED_DAMAGE_MULTIPLIER,
+            _ => 1.0
+        };
+        damage_event.send(DamageEvent {
+            subject: player,
+            source: asteroid,
+            value: damage * multiplier,
+            kind: DamageKind::Collision,
+            hit: Some(HitInfo { point: player_point, normal: into_player, impulse })
+        });
+        damage_event.send(DamageEvent {
+            subject: asteroid,
+            source: player,
+            value: damage,
+            kind: DamageKind::Collision,
+            hit: Some(HitInfo { point: asteroid_point, normal: -into_player, impulse })
+        });
+    }
+}
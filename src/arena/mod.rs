@@ -1,23 +1,51 @@
 use bevy::prelude::*;
-use queues::Queue;
-use crate::arena::generation::{Asteroid, clean_up_map, damage_player, despawn_cubes, destroy_asteroids, spawn_from_queue, spawn_setup, worley_spawner};
+use crate::arena::biomes::{biome_setup, detonate_volatile_asteroids};
+use crate::arena::chunks::ChunkMap;
+use crate::arena::delta::{load_world_delta, save_world_delta, save_world_delta_on_exit, WorldDelta};
+use crate::arena::drift::{push_from_explosions, track_drifting_asteroids};
+use crate::arena::generation::{clean_up_map, despawn_chunks, destroy_asteroids, load_spawn_budget, receive_generated_asteroids, setup_spawn_hasher, spawn_from_queue, spawn_setup, worley_spawner};
+use crate::arena::impact::{impact_damage, record_impact_velocity};
+use crate::arena::ore::{drop_ore, ore_setup};
+use crate::arena::rocks::RockMeshCache;
+use crate::arena::seed::load_world_seed;
+use crate::components::HealthSet;
 use crate::states::{AppStates, GameStates};
 
+pub mod biomes;
+pub mod chunks;
+pub mod delta;
+pub mod drift;
+pub mod fragments;
 pub mod generation;
+pub mod impact;
+pub mod ore;
+pub mod rocks;
+pub mod seed;
 
 pub struct ArenaPlugin;
 
 impl Plugin for ArenaPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Startup, spawn_setup)
-            .add_systems(OnExit(AppStates::Game), clean_up_map)
+            .init_resource::<RockMeshCache>()
+            .init_resource::<WorldDelta>()
+            .init_resource::<ChunkMap>()
+            .add_systems(Startup, (spawn_setup, biome_setup, ore_setup, load_world_seed, load_spawn_budget))
+            .add_systems(OnEnter(AppStates::Game), (setup_spawn_hasher, load_world_delta))
+            .add_systems(OnExit(AppStates::Game), (save_world_delta, clean_up_map).chain())
+            .add_systems(Last, save_world_delta_on_exit.run_if(in_state(AppStates::Game)))
             .add_systems(Update, (
                 worley_spawner,
-                despawn_cubes,
+                receive_generated_asteroids,
+                track_drifting_asteroids,
+                despawn_chunks,
                 spawn_from_queue,
-                destroy_asteroids,
-                damage_player
+                destroy_asteroids.in_set(HealthSet::Death),
+                detonate_volatile_asteroids.in_set(HealthSet::Death),
+                push_from_explosions,
+                drop_ore.in_set(HealthSet::Death),
+                impact_damage.before(record_impact_velocity),
+                record_impact_velocity
             )
                 .run_if(in_state(GameStates::Playing))
                 .run_if(in_state(AppStates::Game)));
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+use bevy::prelude::*;
+use crate::components::cargo::Ore;
+use crate::components::DeathEvent;
+use crate::pickups::{Pickup, PickupHandles, PickupKind, spawn_pickup};
+
+const ORE_PER_SCALE: f32 = 2.0;
+const MAX_DROPS: u32 = 4;
+const DROP_SCATTER_SPEED: f32 = 1.0;
+
+
+// Ore an asteroid releases when destroyed
+#[derive(Component)]
+pub struct OreDeposit {
+    pub ore: Ore,
+    pub amount: u32
+}
+
+impl OreDeposit {
+    pub fn new(ore: Ore, scale: f32) -> Self {
+        Self {
+            ore,
+            amount: ((scale * ORE_PER_SCALE).round() as u32).max(1)
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct OreHandles {
+    materials: HashMap<Ore, Handle<StandardMaterial>>
+}
+
+impl OreHandles {
+    pub fn material(&self, ore: &Ore) -> Handle<StandardMaterial> {
+        self.materials[ore].clone()
+    }
+}
+
+fn ore_material(ore: &Ore) -> StandardMaterial {
+    match ore {
+        Ore::Iron => StandardMaterial {
+            base_color: Color::rgb(0.55, 0.3, 0.2),
+            metallic: 0.8,
+            perceptual_roughness: 0.5,
+            ..default()
+        },
+        Ore::Ice => StandardMaterial {
+            base_color: Color::rgb(0.85, 0.95, 1.0),
+            perceptual_roughness: 0.1,
+            ..default()
+        },
+        Ore::Crystal => StandardMaterial {
+            base_color: Color::rgb(0.6, 0.3, 0.9),
+            emissive: Color::rgb(0.3, 0.1, 0.5),
+            perceptual_roughness: 0.2,
+            ..default()
+        }
+    }
+}
+
+pub fn ore_setup(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+){
+    commands.spawn(OreHandles {
+        materials: Ore::ALL.iter()
+            .map(|ore| (*ore, materials.add(ore_material(ore))))
+            .collect()
+    });
+}
+
+// Splits a destroyed deposit into a few drops scattered around the wreck
+pub fn drop_ore(
+    mut commands: Commands,
+    mut death_event: EventReader<DeathEvent>,
+    deposits: Query<(&Transform, &OreDeposit)>,
+    handle_query: Query<&OreHandles>,
+    pickup_handle_query: Query<&PickupHandles>
+){
+    let handles = handle_query.single();
+    let pickup_handles = pickup_handle_query.single();
+    for death in death_event.iter() {
+        let Ok((transform, deposit)) = deposits.get(death.subject) else {
+            continue;
+        };
+        let drops = deposit.amount.min(MAX_DROPS);
+        for index in 0..drops {
+            // Spread the remainder over the first drops
+            let amount = deposit.amount / drops + u32::from(index < deposit.amount % drops);
+            let angle = index as f32 / drops as f32 * TAU;
+            let direction = Vec3::new(angle.cos(), angle.sin(), 0.0);
+            spawn_pickup(
+                &mut commands,
+                Pickup { kind: PickupKind::Ore(deposit.ore), amount: amount as f32 },
+                transform.translation + direction * transform.scale.x * 0.5,
+                transform.rotation * direction * DROP_SCATTER_SPEED,
+                pickup_handles.mesh.clone(),
+                handles.material(&deposit.ore)
+            );
+        }
+    }
+}
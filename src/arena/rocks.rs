@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy_xpbd_3d::prelude::*;
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+
+const ROCK_VARIANTS: u64 = 32;
+const ROCK_RADIUS: f32 = 0.5;
+const ROCK_SUBDIVISIONS: usize = 2;
+const ROCK_DISPLACEMENT: f64 = 0.35;
+const ROCK_NOISE_FREQUENCY: f64 = 1.2;
+const ROCK_NOISE_OCTAVES: usize = 3;
+
+
+pub struct RockVariant {
+    pub mesh: Handle<Mesh>,
+    hull: Vec<Vec3>
+}
+
+impl RockVariant {
+    // Convex hull of the displaced vertices, scaled to match the asteroid's transform
+    pub fn collider(&self, scale: f32) -> Collider {
+        Collider::convex_hull(self.hull.iter().map(|point| *point * scale).collect())
+            .unwrap_or_else(|| Collider::ball(ROCK_RADIUS * scale))
+    }
+}
+
+// Bounded set of rock shapes, asteroids pick one by hashing their address
+#[derive(Resource, Default)]
+pub struct RockMeshCache {
+    variants: HashMap<u64, RockVariant>
+}
+
+impl RockMeshCache {
+    pub fn get_or_create(
+        &mut self,
+        seed: u32,
+        address_hash: u64,
+        meshes: &mut Assets<Mesh>
+    ) -> &RockVariant {
+        let variant = address_hash % ROCK_VARIANTS;
+        self.variants.entry(variant).or_insert_with(|| {
+            let (mesh, hull) = build_rock(seed.wrapping_add(variant as u32));
+            RockVariant {
+                mesh: meshes.add(mesh),
+                hull
+            }
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.variants.clear();
+    }
+}
+
+// Icosphere with each vertex pushed in or out along its direction by 3D noise
+fn build_rock(noise_seed: u32) -> (Mesh, Vec<Vec3>) {
+    let mut mesh = Mesh::try_from(shape::Icosphere {
+        radius: ROCK_RADIUS,
+        subdivisions: ROCK_SUBDIVISIONS
+    }).expect("Rock icosphere subdivisions out of range");
+
+    let noise = Fbm::<Perlin>::new(noise_seed)
+        .set_frequency(ROCK_NOISE_FREQUENCY)
+        .set_octaves(ROCK_NOISE_OCTAVES);
+
+    let mut hull = Vec::new();
+    if let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION) {
+        for position in positions.iter_mut() {
+            let direction = Vec3::from(*position).normalize();
+            let displacement = noise.get([
+                direction.x as f64,
+                direction.y as f64,
+                direction.z as f64
+            ]);
+            let displaced = direction * ROCK_RADIUS * (1.0 + ROCK_DISPLACEMENT * displacement) as f32;
+            *position = displaced.to_array();
+            hull.push(displaced);
+        }
+    }
+
+    // Flat shading gives the rocks hard faceted edges
+    mesh.duplicate_vertices();
+    mesh.compute_flat_normals();
+    (mesh, hull)
+}
@@ -0,0 +1,71 @@
+use std::env;
+use bevy::prelude::*;
+use crate::arena::generation::MapAddress;
+use crate::util::config_value;
+
+const SEED_ARG: &str = "--seed";
+const SEED_KEY: &str = "seed";
+const RANDOM_SEED: &str = "random";
+
+// Seed for everything procedural in the arena, shared so a run can be replayed
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorldSeed(pub u32);
+
+impl WorldSeed {
+    pub fn random() -> Self {
+        Self(fastrand::u32(..))
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case(RANDOM_SEED) {
+            return Some(Self::random());
+        }
+        value.parse::<u32>().ok().map(Self)
+    }
+
+    // Stable per-cell hash so anything derived from an address replays identically
+    pub fn hash_address(&self, address: &MapAddress) -> u64 {
+        [address.x, address.y, address.z]
+            .iter()
+            .fold(mix(self.0 as u64), |hash, value| mix(hash ^ *value as u32 as u64))
+    }
+}
+
+// SplitMix64 finalizer
+fn mix(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+impl Default for WorldSeed {
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+// Command line takes priority over the config file, falling back to a random seed
+pub fn load_world_seed(
+    mut commands: Commands
+){
+    let seed = seed_from_args()
+        .or_else(|| config_value(SEED_KEY).and_then(|value| WorldSeed::parse(&value)))
+        .unwrap_or_default();
+    println!("World seed: {0}", seed.0);
+    commands.insert_resource(seed);
+}
+
+fn seed_from_args() -> Option<WorldSeed> {
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix("--seed=") {
+            return WorldSeed::parse(value);
+        }
+        if arg == SEED_ARG {
+            return args.next().and_then(|value| WorldSeed::parse(&value));
+        }
+    }
+    None
+}
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use crate::components::{DamageEvent, DamageKind, HitInfo};
+
+// Explosive damage that falls off to nothing at the edge of its radius
+pub struct Blast {
+    pub position: Vec3,
+    pub radius: f32,
+    pub damage: f32,
+    pub impulse: f32
+}
+
+impl Blast {
+    pub fn damage_events<'a>(
+        &'a self,
+        source: Entity,
+        targets: impl Iterator<Item = (Entity, Vec3)> + 'a
+    ) -> impl Iterator<Item = DamageEvent> + 'a {
+        targets.filter_map(move |(target, position)| {
+            let offset = position - self.position;
+            let distance = offset.length();
+            if distance > self.radius {
+                return None;
+            }
+            let falloff = 1.0 - distance / self.radius;
+            Some(DamageEvent {
+                subject: target,
+                source,
+                value: self.damage * falloff,
+                kind: DamageKind::Explosive,
+                hit: Some(HitInfo {
+                    point: position,
+                    normal: offset.normalize_or_zero(),
+                    impulse: self.impulse * falloff
+                })
+            })
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ore {
+    Iron,
+    Ice,
+    Crystal
+}
+
+impl Ore {
+    pub const ALL: [Ore; 3] = [Ore::Iron, Ore::Ice, Ore::Crystal];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ore::Iron => "Iron",
+            Ore::Ice => "Ice",
+            Ore::Crystal => "Crystal"
+        }
+    }
+}
+
+// Ore carried by a ship, limited to a total number of units across all kinds
+#[derive(Component)]
+pub struct Cargo {
+    pub capacity: u32,
+    stored: HashMap<Ore, u32>
+}
+
+impl Cargo {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            capacity,
+            stored: HashMap::new()
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.stored.values().sum()
+    }
+
+    pub fn amount(&self, ore: &Ore) -> u32 {
+        self.stored.get(ore).copied().unwrap_or(0)
+    }
+
+    // Stores as much as fits and returns how many units were taken
+    pub fn add(&mut self, ore: Ore, amount: u32) -> u32 {
+        let accepted = amount.min(self.capacity.saturating_sub(self.total()));
+        if accepted > 0 {
+            *self.stored.entry(ore).or_default() += accepted;
+        }
+        accepted
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::components::Owner;
+use crate::util::config_value;
+
+const FRIENDLY_FIRE_KEY: &str = "friendly_fire";
+
+#[derive(Clone, Copy)]
+pub enum Layer {
+    PlayerShip,
+    HostileShip,
+    PlayerShot,
+    HostileShot,
+    Environment,
+    Pickup
+}
+
+// Implemented by hand, the derive macro checks 2d/3d features this crate doesn't have
+impl PhysicsLayer for Layer {
+    fn to_bits(&self) -> u32 {
+        1 << *self as u32
+    }
+
+    fn all_bits() -> u32 {
+        (1 << (Layer::Pickup as u32 + 1)) - 1
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Faction {
+    Player,
+    // No enemy ships yet, the layers are already there for when there are
+    #[allow(dead_code)]
+    Hostile
+}
+
+// Whether shots hurt ships on the shooter's own side, set per game mode in the config
+#[derive(Resource, Default)]
+pub struct FriendlyFire(pub bool);
+
+impl Faction {
+    fn ship_layer(&self) -> Layer {
+        match self {
+            Faction::Player => Layer::PlayerShip,
+            Faction::Hostile => Layer::HostileShip
+        }
+    }
+
+    fn shot_layer(&self) -> Layer {
+        match self {
+            Faction::Player => Layer::PlayerShot,
+            Faction::Hostile => Layer::HostileShot
+        }
+    }
+
+    pub fn ship_layers(&self) -> CollisionLayers {
+        CollisionLayers::new([self.ship_layer()], [
+            Layer::PlayerShip,
+            Layer::HostileShip,
+            Layer::PlayerShot,
+            Layer::HostileShot,
+            Layer::Environment,
+            Layer::Pickup
+        ])
+    }
+
+    // Shots pass through each other, and through their own side unless friendly fire is on
+    pub fn shot_layers(&self, friendly_fire: &FriendlyFire) -> CollisionLayers {
+        let layers = CollisionLayers::new([self.shot_layer()], [Layer::PlayerShip, Layer::HostileShip, Layer::Environment]);
+        if friendly_fire.0 {
+            layers
+        } else {
+            layers.remove_mask(self.ship_layer())
+        }
+    }
+}
+
+// Asteroids and their fragments, they leave pickups alone so loot doesn't get knocked about
+pub fn environment_layers() -> CollisionLayers {
+    CollisionLayers::new([Layer::Environment], [
+        Layer::PlayerShip,
+        Layer::HostileShip,
+        Layer::PlayerShot,
+        Layer::HostileShot,
+        Layer::Environment
+    ])
+}
+
+// Only the player can collect pickups
+pub fn pickup_layers() -> CollisionLayers {
+    CollisionLayers::new([Layer::Pickup], [Layer::PlayerShip])
+}
+
+// Nothing hurts its own owner, the same faction only with friendly fire, anything without a faction is fair game
+pub fn may_damage(
+    owner: Entity,
+    subject: Entity,
+    factions: &Query<&Faction>,
+    friendly_fire: &FriendlyFire
+) -> bool {
+    if owner == subject {
+        return false;
+    }
+    match (factions.get(owner), factions.get(subject)) {
+        (Ok(owner_faction), Ok(subject_faction)) if owner_faction == subject_faction => friendly_fire.0,
+        _ => true
+    }
+}
+
+// Shots never touch whoever fired them, even when friendly fire lets them hit the rest of that side
+pub fn ignore_owner_collisions(
+    owners: Query<&Owner>,
+    mut pairs: ResMut<BroadCollisionPairs>
+){
+    let owns = |shot: Entity, other: Entity| owners.get(shot).is_ok_and(|owner| owner.0 == other);
+    pairs.0.retain(|(entity1, entity2)| !owns(*entity1, *entity2) && !owns(*entity2, *entity1));
+}
+
+pub fn load_friendly_fire(
+    mut commands: Commands
+){
+    let enabled = config_value(FRIENDLY_FIRE_KEY)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(false);
+    commands.insert_resource(FriendlyFire(enabled));
+}
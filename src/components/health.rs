@@ -1,20 +1,75 @@
+use std::collections::HashSet;
 use bevy::prelude::*;
-use crate::components::{DamageEvent, DeathEvent, Health};
+use bevy::ecs::query::Has;
+use crate::components::{Dead, DamageDealtEvent, DamageEvent, DeathEvent, HealEvent, Health, Resistances, ShieldHitEvent};
+use crate::components::invulnerability::{DamageCooldown, Invulnerable, InvulnerableAfterHit};
+use crate::components::shield::{absorb_damage, Absorb, Shield};
 
 
+type DamageableQuery<'w, 's, 'a> = Query<'w, 's,
+    (&'a mut Health, Option<&'a mut Shield>, Option<&'a Resistances>, Option<&'a mut DamageCooldown>, Option<&'a InvulnerableAfterHit>, Has<Invulnerable>, Has<Dead>)
+>;
+
 pub fn process_damage_to_health(
+    time: Res<Time>,
+    mut commands: Commands,
     mut damage_event: EventReader<DamageEvent>,
+    mut dealt_event: EventWriter<DamageDealtEvent>,
     mut death_event: EventWriter<DeathEvent>,
-    mut query: Query<&mut Health>
+    mut shield_hit_event: EventWriter<ShieldHitEvent>,
+    mut query: DamageableQuery
 ){
+    let now = time.elapsed_seconds();
+    // Invulnerable and Dead are only inserted once commands apply, so track this frame's hits and deaths too
+    let mut hit_this_frame = HashSet::new();
+    let mut died_this_frame = HashSet::new();
     for damage in damage_event.iter() {
-        if let Ok(mut subject_health) = query.get_mut(damage.subject){
-            subject_health.current = (0.0f32).max(subject_health.current - damage.value);
-            println!("Damage: {0} current: {1}", damage.value, subject_health.current);
+        if let Ok((mut subject_health, mut shield, resistances, cooldown, after_hit, invulnerable, dead)) = query.get_mut(damage.subject){
+            if dead || invulnerable || hit_this_frame.contains(&damage.subject) || died_this_frame.contains(&damage.subject) {
+                continue;
+            }
+            if let Some(mut cooldown) = cooldown {
+                if !cooldown.try_hit(damage.source, now) {
+                    continue;
+                }
+            }
+            let shield_before = shield.as_ref().map_or(0.0, |shield| shield.current);
+            let mut layers: Vec<&mut dyn Absorb> = Vec::new();
+            if let Some(shield) = shield.as_deref_mut() {
+                layers.push(shield);
+            }
+            layers.push(&mut *subject_health);
+            let value = damage.value * resistances.map_or(1.0, |resistances| resistances.multiplier(damage.kind));
+            absorb_damage(&mut layers, value);
+            dealt_event.send(DamageDealtEvent { source: damage.source });
+
+            let absorbed = shield_before - shield.as_ref().map_or(0.0, |shield| shield.current);
+            if absorbed > 0.0 {
+                shield_hit_event.send(ShieldHitEvent { subject: damage.subject, absorbed });
+            }
+            println!("Damage: {0} current: {1}", value, subject_health.current);
             if subject_health.current == 0.0 {
-                death_event.send(DeathEvent { subject: damage.subject, source: damage.source});
+                died_this_frame.insert(damage.subject);
+                commands.entity(damage.subject).insert(Dead);
+                death_event.send(DeathEvent { subject: damage.subject, source: damage.source, hit: damage.hit });
+            } else if let Some(InvulnerableAfterHit(seconds)) = after_hit {
+                hit_this_frame.insert(damage.subject);
+                commands.entity(damage.subject).insert(Invulnerable {
+                    timer: Timer::from_seconds(*seconds, TimerMode::Once)
+                });
             }
         }
     }
 }
 
+
+pub fn process_heal_to_health(
+    mut heal_event: EventReader<HealEvent>,
+    mut query: Query<&mut Health, Without<Dead>>
+){
+    for heal in heal_event.iter() {
+        if let Ok(mut subject_health) = query.get_mut(heal.subject){
+            subject_health.current = subject_health.full.min(subject_health.current + heal.value);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+
+// Toggles per second while blinking
+const BLINK_RATE: f32 = 10.0;
+
+
+// Damage is ignored until the timer runs out
+#[derive(Component)]
+pub struct Invulnerable {
+    pub timer: Timer
+}
+
+// Grants Invulnerable for this many seconds whenever the entity takes damage
+#[derive(Component)]
+pub struct InvulnerableAfterHit(pub f32);
+
+// Ignores repeat damage from the same source within the window, such as scraping along one rock
+#[derive(Component)]
+pub struct DamageCooldown {
+    pub seconds: f32,
+    last_hit: HashMap<Entity, f32>
+}
+
+impl DamageCooldown {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            seconds,
+            last_hit: HashMap::new()
+        }
+    }
+
+    // Records the hit and returns whether it may land
+    pub fn try_hit(&mut self, source: Entity, now: f32) -> bool {
+        let seconds = self.seconds;
+        self.last_hit.retain(|_, time| now - *time < seconds);
+        if self.last_hit.contains_key(&source) {
+            return false;
+        }
+        self.last_hit.insert(source, now);
+        true
+    }
+}
+
+pub fn expire_invulnerability(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Invulnerable, &mut Visibility)>
+){
+    for (entity, mut invulnerable, mut visibility) in &mut query {
+        invulnerable.timer.tick(time.delta());
+        if invulnerable.timer.finished() {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
+
+pub fn blink_invulnerable(
+    mut query: Query<(&Invulnerable, &mut Visibility)>
+){
+    for (invulnerable, mut visibility) in &mut query {
+        let visible = ((invulnerable.timer.elapsed_secs() * BLINK_RATE) as u32) % 2 == 0;
+        *visibility = if visible { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
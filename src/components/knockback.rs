@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::components::{DamageEvent, DamageKind};
+
+// Pushed around by hits that have no physical contact behind them, contact hits are already resolved by the solver
+#[derive(Component)]
+pub struct Knockback;
+
+type KnockbackBodyQuery<'w, 's, 'a> = Query<'w, 's,
+    (&'a Position, &'a Rotation, &'a CenterOfMass, &'a InverseMass, &'a InverseInertia, &'a mut LinearVelocity, &'a mut AngularVelocity),
+    With<Knockback>
+>;
+
+pub fn apply_knockback(
+    mut damage_event: EventReader<DamageEvent>,
+    mut bodies: KnockbackBodyQuery
+){
+    for damage in damage_event.iter() {
+        let (DamageKind::Explosive, Some(hit)) = (damage.kind, damage.hit) else {
+            continue;
+        };
+        let Ok((position, rotation, center_of_mass, inverse_mass, inverse_inertia, mut linear, mut angular)) = bodies.get_mut(damage.subject) else {
+            continue;
+        };
+        // Off-centre hits spin the body as well as shoving it
+        let impulse = hit.normal * hit.impulse;
+        let lever = hit.point - (position.0 + rotation.0 * center_of_mass.0);
+        linear.0 += impulse * inverse_mass.0;
+        angular.0 += inverse_inertia.rotated(rotation).0 * lever.cross(impulse);
+    }
+}
@@ -1,10 +1,22 @@
 use bevy::prelude::*;
-use fastrand::f32;
-use crate::components::health::process_damage_to_health;
-use crate::components::points::{damage_points, kill_points};
+use bevy_xpbd_3d::{PhysicsSchedule, PhysicsStepSet};
+use crate::components::faction::{ignore_owner_collisions, load_friendly_fire};
+use crate::components::health::{process_damage_to_health, process_heal_to_health};
+use crate::components::invulnerability::{blink_invulnerable, expire_invulnerability};
+use crate::components::knockback::apply_knockback;
+use crate::components::points::{damage_points, expire_score_multipliers, kill_points};
+use crate::components::shield::{process_shield_restore, recharge_shields};
 use crate::states::{GameStates, AppStates};
+pub mod blast;
+pub mod cargo;
+pub mod faction;
 mod health;
+pub mod invulnerability;
+pub mod knockback;
 mod points;
+pub mod shield;
+#[cfg(test)]
+mod tests;
 
 pub struct ComponentPlugin;
 
@@ -12,32 +24,70 @@ impl Plugin for ComponentPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_event::<DamageEvent>()
+            .add_event::<DamageDealtEvent>()
             .add_event::<DeathEvent>()
+            .add_event::<HealEvent>()
+            .add_event::<ShieldHitEvent>()
+            .add_event::<ShieldRestoreEvent>()
+            .add_systems(Startup, load_friendly_fire)
+            .add_systems(PhysicsSchedule, ignore_owner_collisions
+                .after(PhysicsStepSet::BroadPhase)
+                .before(PhysicsStepSet::Substeps))
+            .configure_sets(Update, (HealthSet::Damage, HealthSet::Death).chain())
             .add_systems(Update,
                          (
-                             process_damage_to_health,
-                             kill_points,
-                             damage_points
+                             process_damage_to_health.in_set(HealthSet::Damage),
+                             process_heal_to_health,
+                             process_shield_restore,
+                             recharge_shields,
+                             apply_knockback,
+                             kill_points.in_set(HealthSet::Death),
+                             damage_points.in_set(HealthSet::Death),
+                             expire_score_multipliers,
+                             expire_invulnerability,
+                             blink_invulnerable.after(expire_invulnerability)
                          )
                              .run_if(in_state(GameStates::Playing))
                              .run_if(in_state(AppStates::Game)));
     }
 }
 
+// Damage lands in Damage, anything reacting to a DeathEvent runs in Death so it sees each death exactly once
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HealthSet {
+    Damage,
+    Death
+}
+
 #[derive(Component)]
 pub struct Owner(pub Entity);
 
+// Marks an entity whose health ran out, it ignores further damage and never dies twice
+#[derive(Component)]
+pub struct Dead;
+
 #[derive(Component)]
 pub struct Health {
     pub full: f32,
     pub current: f32,
 }
 
+// Points awarded to whoever destroys this entity
+#[derive(Component)]
+pub struct PointValue(pub f32);
+
 #[derive(Component)]
 pub struct Score {
     pub current: f32,
 }
 
+// Scales points earned until the timer runs out
+#[derive(Component)]
+pub struct ScoreMultiplier {
+    pub factor: f32,
+    pub timer: Timer
+}
+
 impl Default for Score {
     fn default() -> Self {
         Self {
@@ -46,15 +96,87 @@ impl Default for Score {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DamageKind {
+    Kinetic,
+    Explosive,
+    Thermal,
+    Collision
+}
+
+// Multiplies incoming damage of each kind, below 1 shrugs it off and above 1 is a weakness
+#[derive(Component, Clone, Copy)]
+pub struct Resistances {
+    pub kinetic: f32,
+    pub explosive: f32,
+    pub thermal: f32,
+    pub collision: f32
+}
+
+impl Resistances {
+    pub const NONE: Resistances = Resistances {
+        kinetic: 1.0,
+        explosive: 1.0,
+        thermal: 1.0,
+        collision: 1.0
+    };
+
+    pub fn multiplier(&self, kind: DamageKind) -> f32 {
+        match kind {
+            DamageKind::Kinetic => self.kinetic,
+            DamageKind::Explosive => self.explosive,
+            DamageKind::Thermal => self.thermal,
+            DamageKind::Collision => self.collision
+        }
+    }
+}
+
+// Where a hit landed in world space, the normal points into the subject
+#[derive(Clone, Copy, Debug)]
+pub struct HitInfo {
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub impulse: f32
+}
+
 #[derive(Event)]
 pub struct DamageEvent {
     pub subject: Entity,
     pub source: Entity,
+    pub value: f32,
+    pub kind: DamageKind,
+    pub hit: Option<HitInfo>
+}
+
+// Sent for damage that got past death, invulnerability and cooldowns and was actually taken
+#[derive(Event)]
+pub struct DamageDealtEvent {
+    pub source: Entity
+}
+
+#[derive(Event)]
+pub struct HealEvent {
+    pub subject: Entity,
+    pub value: f32
+}
+
+// Sent when a shield soaks up some or all of a hit
+#[derive(Event)]
+pub struct ShieldHitEvent {
+    pub subject: Entity,
+    pub absorbed: f32
+}
+
+#[derive(Event)]
+pub struct ShieldRestoreEvent {
+    pub subject: Entity,
     pub value: f32
 }
 
 #[derive(Event)]
 pub struct DeathEvent {
     pub subject: Entity,
-    pub source: Entity
+    pub source: Entity,
+    // The hit that did the killing
+    pub hit: Option<HitInfo>
 }
\ No newline at end of file
@@ -1,24 +1,42 @@
 use bevy::prelude::*;
-use crate::components::{DamageEvent, DeathEvent, Score};
+use crate::components::{DamageDealtEvent, DeathEvent, PointValue, Score, ScoreMultiplier};
+
+const DEFAULT_KILL_POINTS: f32 = 10.0;
 
 pub fn kill_points(
-    mut point_trackers: Query<&mut Score>,
+    mut point_trackers: Query<(&mut Score, Option<&ScoreMultiplier>)>,
+    point_values: Query<&PointValue>,
     mut event_reader: EventReader<DeathEvent>
 ){
     for event in event_reader.iter() {
-        if let Ok(mut tracker) = point_trackers.get_mut(event.source){
-            tracker.current += 10.0;
+        if let Ok((mut tracker, multiplier)) = point_trackers.get_mut(event.source){
+            tracker.current += point_values.get(event.subject)
+                .map_or(DEFAULT_KILL_POINTS, |points| points.0)
+                * multiplier.map_or(1.0, |multiplier| multiplier.factor);
         }
     }
 }
 
 pub fn damage_points(
-    mut point_trackers: Query<&mut Score>,
-    mut event_reader: EventReader<DamageEvent>
+    mut point_trackers: Query<(&mut Score, Option<&ScoreMultiplier>)>,
+    mut event_reader: EventReader<DamageDealtEvent>
 ){
     for event in event_reader.iter() {
-        if let Ok(mut tracker) = point_trackers.get_mut(event.source){
-            tracker.current += 1.0;
+        if let Ok((mut tracker, multiplier)) = point_trackers.get_mut(event.source){
+            tracker.current += multiplier.map_or(1.0, |multiplier| multiplier.factor);
+        }
+    }
+}
+
+pub fn expire_score_multipliers(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ScoreMultiplier)>
+){
+    for (entity, mut multiplier) in &mut query {
+        multiplier.timer.tick(time.delta());
+        if multiplier.timer.finished() {
+            commands.entity(entity).remove::<ScoreMultiplier>();
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+use crate::components::{Health, ShieldRestoreEvent};
+
+// Takes damage before Health and recharges once no damage has landed for a while
+#[derive(Component)]
+pub struct Shield {
+    pub full: f32,
+    pub current: f32,
+    // Points restored per second while recharging
+    pub recharge_rate: f32,
+    // Seconds without damage before recharging starts
+    pub recharge_delay: f32,
+    pub since_damage: f32
+}
+
+impl Shield {
+    pub fn new(full: f32, recharge_rate: f32, recharge_delay: f32) -> Self {
+        Self {
+            full,
+            current: full,
+            recharge_rate,
+            recharge_delay,
+            since_damage: 0.0
+        }
+    }
+}
+
+// A layer damage passes through on its way in, returning whatever it could not soak up
+pub trait Absorb {
+    fn absorb(&mut self, damage: f32) -> f32;
+}
+
+impl Absorb for Shield {
+    fn absorb(&mut self, damage: f32) -> f32 {
+        self.since_damage = 0.0;
+        let absorbed = damage.min(self.current);
+        self.current -= absorbed;
+        damage - absorbed
+    }
+}
+
+impl Absorb for Health {
+    fn absorb(&mut self, damage: f32) -> f32 {
+        let absorbed = damage.min(self.current);
+        self.current -= absorbed;
+        damage - absorbed
+    }
+}
+
+// Runs damage through the layers outermost first and returns what got past all of them
+pub fn absorb_damage(layers: &mut [&mut dyn Absorb], damage: f32) -> f32 {
+    layers.iter_mut().fold(damage, |remaining, layer| layer.absorb(remaining))
+}
+
+pub fn recharge_shields(
+    time: Res<Time>,
+    mut query: Query<&mut Shield>
+){
+    let delta = time.delta_seconds();
+    for mut shield in &mut query {
+        shield.since_damage += delta;
+        if shield.since_damage >= shield.recharge_delay && shield.current < shield.full {
+            shield.current = shield.full.min(shield.current + shield.recharge_rate * delta);
+        }
+    }
+}
+
+pub fn process_shield_restore(
+    mut restore_event: EventReader<ShieldRestoreEvent>,
+    mut query: Query<&mut Shield>
+){
+    for restore in restore_event.iter() {
+        if let Ok(mut shield) = query.get_mut(restore.subject) {
+            shield.current = shield.full.min(shield.current + restore.value);
+        }
+    }
+}
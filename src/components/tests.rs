@@ -0,0 +1,146 @@
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::*;
+use crate::components::{ComponentPlugin, Dead, DamageEvent, DamageKind, DeathEvent, Health, PointValue, Score};
+use crate::components::invulnerability::InvulnerableAfterHit;
+use crate::components::shield::Shield;
+use crate::states::{AppStates, GameStates};
+
+fn test_app() -> App {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .add_state::<GameStates>()
+        .add_state::<AppStates>()
+        .add_plugins(ComponentPlugin);
+    app.world.resource_mut::<NextState<AppStates>>().set(AppStates::Game);
+    app.update();
+    app
+}
+
+fn spawn_target(app: &mut App, health: f32) -> Entity {
+    app.world.spawn((Health { full: health, current: health }, PointValue(5.0))).id()
+}
+
+fn damage(app: &mut App, subject: Entity, source: Entity, value: f32) {
+    app.world.send_event(DamageEvent {
+        subject,
+        source,
+        value,
+        kind: DamageKind::Kinetic,
+        hit: None
+    });
+}
+
+fn deaths(app: &App, reader: &mut ManualEventReader<DeathEvent>) -> Vec<Entity> {
+    reader.iter(app.world.resource::<Events<DeathEvent>>())
+        .map(|death| death.subject)
+        .collect()
+}
+
+#[test]
+fn several_hits_in_one_frame_die_once() {
+    let mut app = test_app();
+    let mut reader = ManualEventReader::default();
+    let source = app.world.spawn_empty().id();
+    let target = spawn_target(&mut app, 1.0);
+
+    for _ in 0..3 {
+        damage(&mut app, target, source, 1.0);
+    }
+    app.update();
+
+    assert_eq!(deaths(&app, &mut reader), vec![target]);
+    assert!(app.world.get::<Dead>(target).is_some());
+}
+
+#[test]
+fn damage_after_death_does_not_die_again() {
+    let mut app = test_app();
+    let mut reader = ManualEventReader::default();
+    let source = app.world.spawn_empty().id();
+    let target = spawn_target(&mut app, 1.0);
+
+    damage(&mut app, target, source, 1.0);
+    app.update();
+    assert_eq!(deaths(&app, &mut reader), vec![target]);
+
+    damage(&mut app, target, source, 1.0);
+    app.update();
+    app.update();
+    assert!(deaths(&app, &mut reader).is_empty());
+}
+
+#[test]
+fn kill_points_are_awarded_once() {
+    let mut app = test_app();
+    let source = app.world.spawn(Score::default()).id();
+    let target = spawn_target(&mut app, 1.0);
+
+    damage(&mut app, target, source, 1.0);
+    damage(&mut app, target, source, 1.0);
+    app.update();
+    app.update();
+
+    // 5 for the kill and 1 for the hit that landed, nothing for hitting the wreck
+    assert_eq!(app.world.get::<Score>(source).unwrap().current, 6.0);
+}
+
+#[test]
+fn damage_after_death_scores_nothing() {
+    let mut app = test_app();
+    let source = app.world.spawn(Score::default()).id();
+    let target = spawn_target(&mut app, 1.0);
+
+    damage(&mut app, target, source, 1.0);
+    app.update();
+    let score = app.world.get::<Score>(source).unwrap().current;
+
+    damage(&mut app, target, source, 1.0);
+    app.update();
+    assert_eq!(app.world.get::<Score>(source).unwrap().current, score);
+}
+
+#[test]
+fn damage_while_invulnerable_scores_nothing() {
+    let mut app = test_app();
+    let source = app.world.spawn(Score::default()).id();
+    let target = spawn_target(&mut app, 10.0);
+    app.world.entity_mut(target).insert(InvulnerableAfterHit(5.0));
+
+    damage(&mut app, target, source, 1.0);
+    app.update();
+    damage(&mut app, target, source, 1.0);
+    app.update();
+
+    assert_eq!(app.world.get::<Score>(source).unwrap().current, 1.0);
+    assert_eq!(app.world.get::<Health>(target).unwrap().current, 9.0);
+}
+
+#[test]
+fn survivors_do_not_die() {
+    let mut app = test_app();
+    let mut reader = ManualEventReader::default();
+    let source = app.world.spawn_empty().id();
+    let target = spawn_target(&mut app, 10.0);
+
+    damage(&mut app, target, source, 4.0);
+    app.update();
+
+    assert!(deaths(&app, &mut reader).is_empty());
+    assert!(app.world.get::<Dead>(target).is_none());
+    assert_eq!(app.world.get::<Health>(target).unwrap().current, 6.0);
+}
+
+#[test]
+fn shield_absorbs_before_health() {
+    let mut app = test_app();
+    let source = app.world.spawn_empty().id();
+    let target = spawn_target(&mut app, 10.0);
+    app.world.entity_mut(target).insert(Shield::new(5.0, 1.0, 10.0));
+
+    damage(&mut app, target, source, 8.0);
+    app.update();
+
+    assert_eq!(app.world.get::<Shield>(target).unwrap().current, 0.0);
+    assert_eq!(app.world.get::<Health>(target).unwrap().current, 7.0);
+}
@@ -5,14 +5,30 @@ use bevy::{
         render_resource::*,
     },
 };
+use std::collections::HashMap;
 use std::time::Duration;
 use itertools::Itertools;
 use crate::effects::{Explosion, ExplosionEvent, Particles, ParticlesMaterial};
 use crate::util::{Lifetime};
 
 const EXPLODE_LIFE: f32 = 5.0;
+// Power only sets how dense a burst is, capped so a big rock can't build a mesh of millions of points
+const PARTICLES_PER_POWER: f32 = 10.0;
+const MAX_EXPLOSION_PARTICLES: f32 = 12.0;
 
 
+// One burst mesh per particle count, so chains of explosions don't each build their own
+#[derive(Resource, Default)]
+pub struct ParticleMeshes(HashMap<u32, Handle<Mesh>>);
+
+impl ParticleMeshes {
+    fn get_or_create(&mut self, num_particles: u32, meshes: &mut Assets<Mesh>) -> Handle<Mesh> {
+        self.0.entry(num_particles)
+            .or_insert_with(|| meshes.add(particle_mesh(num_particles)))
+            .clone()
+    }
+}
+
 impl From<Particles> for Mesh {
     fn from(particles: Particles) -> Self {
         let extent = 0.1 / 2.0;
@@ -59,13 +75,66 @@ pub fn update_time_for_particles_material(
     time: Res<Time>,
 ){
     for material in materials.iter_mut() {
-        material.1.time = time.elapsed_seconds() as f32;
+        material.1.time = time.elapsed_seconds();
+    }
+}
+
+fn particle_mesh(num_particles: u32) -> Mesh {
+    let mut particles = Mesh::from(Particles { num_particles });
+
+    if let Some(VertexAttributeValues::Float32x3(
+                    positions,
+                )) = particles.attribute(Mesh::ATTRIBUTE_POSITION)
+    {
+        let colors: Vec<[f32; 4]> = positions
+            .iter()
+            .map(|[r, g, b]| {
+                [
+                    *r * 2.0 - 1.0,
+                    *g * 2.0 - 1.0,
+                    *b * 2.0 - 1.0,
+                    1.,
+                ]
+            })
+            .collect();
+        particles.insert_attribute(
+            Mesh::ATTRIBUTE_COLOR,
+            colors,
+        );
     }
+    particles
+}
+
+fn spawn_particle_burst(
+    commands: &mut Commands,
+    materials: &mut Assets<ParticlesMaterial>,
+    mesh: Handle<Mesh>,
+    position: Vec3,
+    now: f32,
+    life: f32
+){
+    commands.spawn((
+        MaterialMeshBundle {
+            mesh,
+            transform: Transform::from_translation(position),
+            material: materials.add(ParticlesMaterial {
+                time: now,
+                start: now,
+                end: now + life,
+            }),
+            ..default()
+        },
+        Explosion,
+        Lifetime {
+            timer: Timer::new(Duration::from_secs_f32(life), TimerMode::Once)
+        },
+    ));
 }
 
 pub fn spawn_explosions(
     time: Res<Time>,
     mut commands: Commands,
+    mut particle_meshes: ResMut<ParticleMeshes>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ParticlesMaterial>>,
     mut explosion_event: EventReader<ExplosionEvent>
@@ -73,47 +142,14 @@ pub fn spawn_explosions(
 {
     let now = time.elapsed_seconds();
     for explosion in explosion_event.iter(){
-        let mut particles =
-            Mesh::from(Particles { num_particles: (explosion.power * 10.0) as u32 });
-
-        if let Some(VertexAttributeValues::Float32x3(
-                        positions,
-                    )) = particles.attribute(Mesh::ATTRIBUTE_POSITION)
-        {
-            let colors: Vec<[f32; 4]> = positions
-                .iter()
-                .map(|[r, g, b]| {
-                    [
-                        *r * 2.0 - 1.0,
-                        *g * 2.0 - 1.0,
-                        *b * 2.0 - 1.0,
-                        1.,
-                    ]
-                })
-                .collect();
-            particles.insert_attribute(
-                Mesh::ATTRIBUTE_COLOR,
-                colors,
-            );
-        }
-
-        commands.spawn((
-            MaterialMeshBundle {
-                mesh: meshes.add(particles),
-                transform: Transform::from_xyz(
-                    explosion.position.x, explosion.position.y, explosion.position.z
-                ),
-                material: materials.add(ParticlesMaterial {
-                    time: now,
-                    start: now,
-                    end: now + EXPLODE_LIFE,
-                }),
-                ..default()
-            },
-            Explosion,
-            Lifetime {
-                timer: Timer::new(Duration::from_secs_f32(EXPLODE_LIFE), TimerMode::Once)
-            },
-        ));
+        let num_particles = (explosion.power * PARTICLES_PER_POWER).min(MAX_EXPLOSION_PARTICLES) as u32;
+        spawn_particle_burst(
+            &mut commands,
+            &mut materials,
+            particle_meshes.get_or_create(num_particles, &mut meshes),
+            explosion.position,
+            now,
+            EXPLODE_LIFE
+        );
     }
 }
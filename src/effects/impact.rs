@@ -0,0 +1,68 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use crate::components::DamageEvent;
+use crate::effects::ImpactFlash;
+use crate::util::Lifetime;
+
+const FLASH_LIFE: f32 = 0.15;
+const FLASH_RADIUS: f32 = 0.15;
+// Pulled back out of the surface so the flash isn't buried in the rock
+const FLASH_OFFSET: f32 = 0.05;
+
+
+#[derive(Component)]
+pub struct ImpactFlashHandles {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>
+}
+
+pub fn setup_impact_flash(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+){
+    let mesh = Mesh::try_from(shape::Icosphere {
+        radius: FLASH_RADIUS,
+        subdivisions: 1
+    }).expect("Impact icosphere subdivisions out of range");
+    commands.spawn(ImpactFlashHandles {
+        mesh: meshes.add(mesh),
+        material: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.9, 0.6),
+            emissive: Color::rgb(1.0, 0.7, 0.3),
+            unlit: true,
+            ..default()
+        })
+    });
+}
+
+// A flash wherever a hit landed, sharing one mesh and material between all of them
+pub fn spawn_impact_flashes(
+    mut commands: Commands,
+    mut damage_event: EventReader<DamageEvent>,
+    handle_query: Query<&ImpactFlashHandles>
+){
+    let handles = handle_query.single();
+    for hit in damage_event.iter().filter_map(|damage| damage.hit) {
+        commands.spawn((
+            PbrBundle {
+                mesh: handles.mesh.clone(),
+                material: handles.material.clone(),
+                transform: Transform::from_translation(hit.point - hit.normal * FLASH_OFFSET),
+                ..default()
+            },
+            ImpactFlash,
+            Lifetime {
+                timer: Timer::new(Duration::from_secs_f32(FLASH_LIFE), TimerMode::Once)
+            }
+        ));
+    }
+}
+
+pub fn shrink_impact_flashes(
+    mut flashes: Query<(&Lifetime, &mut Transform), With<ImpactFlash>>
+){
+    for (lifetime, mut transform) in &mut flashes {
+        transform.scale = Vec3::splat(lifetime.timer.percent_left());
+    }
+}
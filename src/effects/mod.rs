@@ -1,11 +1,15 @@
 use bevy::prelude::*;
 use bevy::reflect::{TypePath, TypeUuid};
 use bevy::render::render_resource::{AsBindGroup, ShaderRef};
-use crate::effects::explosion::{spawn_explosions, update_time_for_particles_material};
+use crate::effects::explosion::{ParticleMeshes, spawn_explosions, update_time_for_particles_material};
+use crate::effects::impact::{setup_impact_flash, shrink_impact_flashes, spawn_impact_flashes};
+use crate::effects::shield::{setup_shield_flash, spawn_shield_flashes, swell_shield_flashes};
 use crate::states::{AppStates, GameStates};
 use crate::util::{clean_up, decay_after_lifetime};
 
 mod explosion;
+mod impact;
+mod shield;
 
 pub struct EffectsPlugin;
 
@@ -13,7 +17,9 @@ impl Plugin for EffectsPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_event::<ExplosionEvent>()
-            .add_systems(OnExit(AppStates::Game), clean_up::<Explosion>)
+            .init_resource::<ParticleMeshes>()
+            .add_systems(Startup, (setup_shield_flash, setup_impact_flash))
+            .add_systems(OnExit(AppStates::Game), (clean_up::<Explosion>, clean_up::<ShieldFlash>, clean_up::<ImpactFlash>))
             .add_plugins((
                 MaterialPlugin::<ParticlesMaterial>::default(),
             ))
@@ -21,6 +27,12 @@ impl Plugin for EffectsPlugin {
                 update_time_for_particles_material,
                 spawn_explosions,
                 decay_after_lifetime::<Explosion>,
+                spawn_shield_flashes,
+                swell_shield_flashes,
+                decay_after_lifetime::<ShieldFlash>,
+                spawn_impact_flashes,
+                shrink_impact_flashes,
+                decay_after_lifetime::<ImpactFlash>,
             )
                 .run_if(in_state(GameStates::Playing))
                 .run_if(in_state(AppStates::Game)));
@@ -40,6 +52,14 @@ pub struct Explodeable;
 #[derive(Component)]
 pub struct Explosion;
 
+#[derive(Component)]
+pub struct ImpactFlash;
+
+#[derive(Component)]
+pub struct ShieldFlash {
+    growth: f32
+}
+
 #[derive(AsBindGroup, TypeUuid, TypePath, Debug, Clone)]
 #[uuid = "00cfdf10-7270-490d-8841-cf08b476303a"]
 pub struct ParticlesMaterial {
@@ -0,0 +1,76 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use crate::components::ShieldHitEvent;
+use crate::effects::ShieldFlash;
+use crate::util::Lifetime;
+
+const FLASH_LIFE: f32 = 0.25;
+const FLASH_RADIUS: f32 = 1.2;
+// How far the bubble swells over its life on a hit that soaks up FLASH_STRONG_HIT or more
+const FLASH_GROWTH: f32 = 0.5;
+const FLASH_STRONG_HIT: f32 = 20.0;
+
+
+#[derive(Component)]
+pub struct ShieldFlashHandles {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>
+}
+
+pub fn setup_shield_flash(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+){
+    let mesh = Mesh::try_from(shape::Icosphere {
+        radius: FLASH_RADIUS,
+        subdivisions: 3
+    }).expect("Shield icosphere subdivisions out of range");
+    commands.spawn(ShieldFlashHandles {
+        mesh: meshes.add(mesh),
+        material: materials.add(StandardMaterial {
+            base_color: Color::rgba(0.3, 0.7, 1.0, 0.25),
+            emissive: Color::rgb(0.1, 0.3, 0.6),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })
+    });
+}
+
+// A brief bubble around whatever the shield protected
+pub fn spawn_shield_flashes(
+    mut commands: Commands,
+    mut shield_hit_event: EventReader<ShieldHitEvent>,
+    handle_query: Query<&ShieldFlashHandles>
+){
+    let handles = handle_query.single();
+    for hit in shield_hit_event.iter() {
+        let Some(mut subject) = commands.get_entity(hit.subject) else {
+            continue;
+        };
+        subject.with_children(|parent| {
+            parent.spawn((
+                PbrBundle {
+                    mesh: handles.mesh.clone(),
+                    material: handles.material.clone(),
+                    ..default()
+                },
+                ShieldFlash {
+                    growth: FLASH_GROWTH * (hit.absorbed / FLASH_STRONG_HIT).min(1.0)
+                },
+                Lifetime {
+                    timer: Timer::new(Duration::from_secs_f32(FLASH_LIFE), TimerMode::Once)
+                }
+            ));
+        });
+    }
+}
+
+pub fn swell_shield_flashes(
+    mut flashes: Query<(&ShieldFlash, &Lifetime, &mut Transform)>
+){
+    for (flash, lifetime, mut transform) in &mut flashes {
+        transform.scale = Vec3::splat(1.0 + lifetime.timer.percent() * flash.growth);
+    }
+}
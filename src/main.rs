@@ -7,21 +7,18 @@ mod arena;
 mod effects;
 mod components;
 mod spawnable;
+mod pickups;
 
 // Entrypoint for the main game binary
 use bevy::{
     prelude::*,
 };
-use bevy::window::PresentMode;
 use bevy_xpbd_3d::prelude::*;
-use leafwing_input_manager::{
-    prelude::*,
-    Actionlike
-};
 use crate::arena::ArenaPlugin;
 use crate::camera::CameraPlugin;
 use crate::components::ComponentPlugin;
 use crate::effects::EffectsPlugin;
+use crate::pickups::PickupsPlugin;
 use crate::player::PlayerPlugin;
 use crate::spawnable::SpawnablesPlugin;
 use crate::states::*;
@@ -47,6 +44,7 @@ fn main() {
             ComponentPlugin,
             StatesPlugin,
             SpawnablesPlugin,
+            PickupsPlugin,
             EffectsPlugin,
             UiPlugin,
             CameraPlugin
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::components::cargo::Cargo;
+use crate::components::{HealEvent, ScoreMultiplier, ShieldRestoreEvent};
+use crate::pickups::{Pickup, PickupEvent, PickupKind, PickupVisual};
+use crate::player::Player;
+use crate::spawnable::{Arsenal, MissileLauncher};
+
+const BOB_HEIGHT: f32 = 0.15;
+const BOB_SPEED: f32 = 2.0;
+const SPIN_SPEED: f32 = 1.5;
+const SCORE_MULTIPLIER_SECONDS: f32 = 15.0;
+
+
+// Checks every pickup still touching the player, so ore left behind by a full hold is taken once there is room
+pub fn collect_pickups(
+    mut commands: Commands,
+    mut pickups: Query<&mut Pickup>,
+    collectors: Query<(Entity, &CollidingEntities, Option<&Cargo>), With<Player>>,
+    mut pickup_event: EventWriter<PickupEvent>
+){
+    for (collector, colliding, cargo) in &collectors {
+        // Cargo only fills once apply_pickups runs, so count down the room this frame's ore will take
+        let mut room = cargo.map_or(0, |cargo| cargo.capacity.saturating_sub(cargo.total()));
+        for &pickup_entity in colliding.iter() {
+            let Ok(mut pickup) = pickups.get_mut(pickup_entity) else {
+                continue;
+            };
+            // Ore only goes as far as the hold has room, the rest keeps floating
+            let amount = match pickup.kind {
+                PickupKind::Ore(_) => {
+                    let taken = (pickup.amount as u32).min(room);
+                    room -= taken;
+                    taken as f32
+                },
+                _ => pickup.amount
+            };
+            if amount <= 0.0 {
+                continue;
+            }
+            pickup_event.send(PickupEvent { collector, kind: pickup.kind, amount });
+            pickup.amount -= amount;
+            if pickup.amount <= 0.0 {
+                commands.entity(pickup_entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+pub fn apply_pickups(
+    mut commands: Commands,
+    mut pickup_event: EventReader<PickupEvent>,
+    mut heal_event: EventWriter<HealEvent>,
+    mut shield_restore_event: EventWriter<ShieldRestoreEvent>,
+    mut holds: Query<&mut Cargo>,
+    mut armories: Query<(Option<&mut Arsenal>, Option<&mut MissileLauncher>)>
+){
+    for pickup in pickup_event.iter() {
+        match pickup.kind {
+            PickupKind::Health => heal_event.send(HealEvent {
+                subject: pickup.collector,
+                value: pickup.amount
+            }),
+            PickupKind::Shield => shield_restore_event.send(ShieldRestoreEvent {
+                subject: pickup.collector,
+                value: pickup.amount
+            }),
+            PickupKind::Ammo => {
+                let Ok((arsenal, launcher)) = armories.get_mut(pickup.collector) else {
+                    continue;
+                };
+                let magazines = pickup.amount as u32;
+                if let Some(mut arsenal) = arsenal {
+                    for ammo in arsenal.weapons_mut().filter_map(|weapon| weapon.ammo.as_mut()) {
+                        ammo.refill(ammo.magazine * magazines);
+                    }
+                }
+                if let Some(mut launcher) = launcher {
+                    let rounds = launcher.ammo.magazine * magazines;
+                    launcher.ammo.refill(rounds);
+                }
+            },
+            PickupKind::ScoreMultiplier => {
+                commands.entity(pickup.collector).insert(ScoreMultiplier {
+                    factor: pickup.amount,
+                    timer: Timer::from_seconds(SCORE_MULTIPLIER_SECONDS, TimerMode::Once)
+                });
+            },
+            PickupKind::Ore(ore) => {
+                if let Ok(mut cargo) = holds.get_mut(pickup.collector) {
+                    cargo.add(ore, pickup.amount as u32);
+                }
+            }
+        }
+    }
+}
+
+pub fn bob_and_spin(
+    time: Res<Time>,
+    mut visuals: Query<(&PickupVisual, &mut Transform)>
+){
+    let now = time.elapsed_seconds();
+    for (visual, mut transform) in &mut visuals {
+        transform.translation.y = ((now + visual.phase) * BOB_SPEED).sin() * BOB_HEIGHT;
+        transform.rotation = Quat::from_rotation_y((now + visual.phase) * SPIN_SPEED);
+    }
+}
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use crate::arena::generation::Asteroid;
+use crate::components::DeathEvent;
+use crate::pickups::{Pickup, PickupHandles, PickupKind, spawn_pickup};
+use crate::util::config_value;
+
+// Salt so loot rolls don't line up with the fragments rolled from the same seed
+const LOOT_SALT: u64 = 0x6969_9696_C3C3_3C3C;
+const LOOT_SCATTER_SPEED: f32 = 0.5;
+
+
+pub struct LootEntry {
+    pub kind: PickupKind,
+    pub chance: f32,
+    pub amount: f32
+}
+
+// What a destroyed asteroid may drop, each entry rolled on its own
+#[derive(Resource)]
+pub struct LootTable {
+    pub entries: Vec<LootEntry>
+}
+
+impl Default for LootTable {
+    fn default() -> Self {
+        Self {
+            entries: vec![
+                LootEntry { kind: PickupKind::Health, chance: 0.08, amount: 20.0 },
+                LootEntry { kind: PickupKind::ScoreMultiplier, chance: 0.03, amount: 2.0 },
+                LootEntry { kind: PickupKind::Shield, chance: 0.05, amount: 25.0 },
+                LootEntry { kind: PickupKind::Ammo, chance: 0.06, amount: 1.0 }
+            ]
+        }
+    }
+}
+
+// Reads `chance amount` pairs, so `loot_health = 0.1 25` drops 25 health one time in ten
+fn parse_loot_value(value: &str) -> Option<(f32, f32)> {
+    let mut fields = value.split_whitespace();
+    let chance = fields.next()?.parse().ok()?;
+    let amount = fields.next()?.parse().ok()?;
+    Some((chance, amount))
+}
+
+pub fn load_loot_table(
+    mut commands: Commands
+){
+    let mut table = LootTable::default();
+    for entry in table.entries.iter_mut() {
+        let key = match entry.kind {
+            PickupKind::Health => "loot_health",
+            PickupKind::ScoreMultiplier => "loot_score_multiplier",
+            PickupKind::Shield => "loot_shield",
+            PickupKind::Ammo => "loot_ammo",
+            PickupKind::Ore(_) => continue
+        };
+        if let Some((chance, amount)) = config_value(key).as_deref().and_then(parse_loot_value) {
+            entry.chance = chance;
+            entry.amount = amount;
+        }
+    }
+    commands.insert_resource(table);
+}
+
+pub fn drop_loot(
+    mut commands: Commands,
+    table: Res<LootTable>,
+    mut death_event: EventReader<DeathEvent>,
+    asteroids: Query<(&Transform, &Asteroid)>,
+    handle_query: Query<&PickupHandles>
+){
+    let handles = handle_query.single();
+    for death in death_event.iter() {
+        let Ok((transform, asteroid)) = asteroids.get(death.subject) else {
+            continue;
+        };
+        // Seeded by the asteroid, so the same rock always drops the same loot
+        let mut rng = fastrand::Rng::with_seed(asteroid.seed ^ LOOT_SALT);
+        for entry in table.entries.iter() {
+            if rng.f32() >= entry.chance {
+                continue;
+            }
+            let Some(material) = handles.material(&entry.kind) else {
+                continue;
+            };
+            let direction = Vec3::new(rng.f32() - 0.5, rng.f32() - 0.5, rng.f32() - 0.5).normalize_or_zero();
+            spawn_pickup(
+                &mut commands,
+                Pickup { kind: entry.kind, amount: entry.amount },
+                transform.translation,
+                direction * LOOT_SCATTER_SPEED,
+                handles.mesh.clone(),
+                material
+            );
+        }
+    }
+}
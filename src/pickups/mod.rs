@@ -0,0 +1,160 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::components::cargo::Ore;
+use crate::components::HealthSet;
+use crate::components::faction::pickup_layers;
+use crate::pickups::collect::{apply_pickups, bob_and_spin, collect_pickups};
+use crate::pickups::loot::{drop_loot, load_loot_table};
+use crate::states::{AppStates, GameStates};
+use crate::util::{clean_up, decay_after_lifetime, Lifetime};
+
+mod collect;
+mod loot;
+
+const PICKUP_RADIUS: f32 = 0.3;
+const PICKUP_LIFETIME: Duration = Duration::from_secs(30);
+
+pub struct PickupsPlugin;
+
+impl Plugin for PickupsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<PickupEvent>()
+            .add_systems(Startup, (setup, load_loot_table))
+            .add_systems(OnExit(AppStates::Game), clean_up::<Pickup>)
+            .add_systems(Update, (
+                drop_loot.in_set(HealthSet::Death),
+                collect_pickups,
+                apply_pickups,
+                bob_and_spin,
+                decay_after_lifetime::<Pickup>
+            ).run_if(in_state(GameStates::Playing))
+                .run_if(in_state(AppStates::Game)));
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+){
+    let mesh = Mesh::try_from(shape::Icosphere {
+        radius: PICKUP_RADIUS,
+        subdivisions: 0
+    }).expect("Pickup icosphere subdivisions out of range");
+    commands.spawn(PickupHandles {
+        mesh: meshes.add(mesh),
+        health: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.2, 0.9, 0.3),
+            emissive: Color::rgb(0.1, 0.5, 0.1),
+            ..default()
+        }),
+        score_multiplier: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.8, 0.2),
+            emissive: Color::rgb(0.6, 0.4, 0.0),
+            metallic: 0.8,
+            ..default()
+        }),
+        shield: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.3, 0.7, 1.0),
+            emissive: Color::rgb(0.1, 0.3, 0.6),
+            ..default()
+        }),
+        ammo: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.5, 0.1),
+            emissive: Color::rgb(0.5, 0.2, 0.0),
+            ..default()
+        })
+    });
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PickupKind {
+    // Restores this much health
+    Health,
+    // Multiplies points by the amount for a while
+    ScoreMultiplier,
+    // Tops the shield up by this much
+    Shield,
+    // Adds this many magazines to the reserve of every weapon that uses ammo
+    Ammo,
+    // Units of ore for the cargo hold
+    Ore(Ore)
+}
+
+#[derive(Component)]
+pub struct Pickup {
+    pub kind: PickupKind,
+    pub amount: f32
+}
+
+// The mesh that bobs and spins, kept on a child so it does not move the collider
+#[derive(Component)]
+pub struct PickupVisual {
+    phase: f32
+}
+
+// Sent when a collector flies into a pickup, effects are applied from this rather than on contact
+#[derive(Event)]
+pub struct PickupEvent {
+    pub collector: Entity,
+    pub kind: PickupKind,
+    pub amount: f32
+}
+
+#[derive(Component)]
+pub struct PickupHandles {
+    pub mesh: Handle<Mesh>,
+    health: Handle<StandardMaterial>,
+    score_multiplier: Handle<StandardMaterial>,
+    shield: Handle<StandardMaterial>,
+    ammo: Handle<StandardMaterial>
+}
+
+impl PickupHandles {
+    // Ore pickups take their look from the ore they hold, so they bring their own material
+    pub fn material(&self, kind: &PickupKind) -> Option<Handle<StandardMaterial>> {
+        match kind {
+            PickupKind::Health => Some(self.health.clone()),
+            PickupKind::ScoreMultiplier => Some(self.score_multiplier.clone()),
+            PickupKind::Shield => Some(self.shield.clone()),
+            PickupKind::Ammo => Some(self.ammo.clone()),
+            PickupKind::Ore(_) => None
+        }
+    }
+}
+
+pub fn spawn_pickup(
+    commands: &mut Commands,
+    pickup: Pickup,
+    position: Vec3,
+    velocity: Vec3,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>
+){
+    commands.spawn((
+        SpatialBundle::default(),
+        pickup,
+        Lifetime {
+            timer: Timer::new(PICKUP_LIFETIME, TimerMode::Once)
+        },
+        Collider::ball(PICKUP_RADIUS),
+        Sensor,
+        pickup_layers(),
+        RigidBody::Kinematic,
+        Position(position),
+        LinearVelocity(velocity)
+    )).with_children(|parent| {
+        parent.spawn((
+            PbrBundle {
+                mesh,
+                material,
+                ..default()
+            },
+            // Offset by position so neighbouring pickups don't bob in step
+            PickupVisual { phase: position.x + position.y + position.z }
+        ));
+    });
+}
@@ -15,6 +15,10 @@ pub enum PlayerAction {
 
     // Weapon Controls
     Shoot,
+    NextWeapon,
+    PreviousWeapon,
+    FireMissile,
+    Reload,
 
     // System Actions
     Menu
@@ -36,6 +40,10 @@ impl PlayerAction {
             Self::Pitch => UserInput::from(InputKind::SingleAxis(SingleAxis::mouse_motion_y())),
             Self::Yaw => UserInput::from(InputKind::SingleAxis(SingleAxis::mouse_motion_x())),
             Self::Shoot => UserInput::Single(InputKind::Mouse(MouseButton::Left)),
+            Self::NextWeapon => UserInput::Single(InputKind::MouseWheel(MouseWheelDirection::Up)),
+            Self::Reload => UserInput::Single(InputKind::Keyboard(KeyCode::R)),
+            Self::FireMissile => UserInput::Single(InputKind::Mouse(MouseButton::Right)),
+            Self::PreviousWeapon => UserInput::Single(InputKind::MouseWheel(MouseWheelDirection::Down)),
             Self::Menu => UserInput::Single(InputKind::Keyboard(KeyCode::Escape))
         }
     }
@@ -3,10 +3,20 @@ use bevy_xpbd_3d::{ prelude::*, PhysicsSchedule, PhysicsStepSet };
 use bevy::prelude::*;
 use leafwing_input_manager::{Actionlike, InputManagerBundle};
 use leafwing_input_manager::prelude::{ActionState, InputManagerPlugin, InputMap};
-use crate::spawnable::{Cannon, NextShot, WeaponBundle, WeaponOptions};
+use crate::components::DamageKind;
+use crate::spawnable::{Arsenal, MissileLauncher, NextShot, TargetLock, Trigger, Weapon, WeaponBundle, WeaponKind, WeaponOptions};
+use crate::spawnable::ammo::Ammo;
+use crate::spawnable::heat::Heat;
+use crate::spawnable::payload::{Crit, Falloff};
 use crate::states::{AppStates, GameStates};
-use crate::arena::generation::{SpawnArea, PreviousSpawnUpdate, MapAddress};
-use crate::components::{DeathEvent, Health, Score};
+use crate::arena::generation::MapSpawnerBundle;
+use crate::arena::impact::ImpactVelocity;
+use crate::components::{DeathEvent, Health, HealthSet, Score};
+use crate::components::cargo::Cargo;
+use crate::components::faction::Faction;
+use crate::components::invulnerability::{DamageCooldown, InvulnerableAfterHit};
+use crate::components::knockback::Knockback;
+use crate::components::shield::Shield;
 use crate::effects::ExplosionEvent;
 use crate::player::input::PlayerAction;
 
@@ -15,6 +25,15 @@ const MOVE_SPEED:f32 = 50.0;
 const PITCH_SENSITIVITY: f32 = 10.0;
 const ROLL_SPEED:f32 = 20.0;
 const STRAFE_SPEED:f32 = 10.0;
+const CARGO_CAPACITY:u32 = 50;
+const INVULNERABLE_SECONDS:f32 = 1.0;
+const DAMAGE_COOLDOWN_SECONDS:f32 = 0.5;
+const SHIELD_FULL:f32 = 50.0;
+const SHIELD_RECHARGE_RATE:f32 = 10.0;
+const SHIELD_RECHARGE_DELAY:f32 = 3.0;
+const MISSILE_MAGAZINE:u32 = 2;
+const MISSILE_RESERVE:u32 = 6;
+const MISSILE_RELOAD_SECONDS:f32 = 2.0;
 
 pub struct PlayerPlugin;
 
@@ -24,7 +43,7 @@ impl Plugin for PlayerPlugin {
             .add_plugins(InputManagerPlugin::<PlayerAction>::default())
             .add_systems(Update, (
                 player_input,
-                player_death
+                player_death.in_set(HealthSet::Death)
             ).run_if(in_state(GameStates::Playing))
              .run_if(in_state(AppStates::Game)))
 
@@ -34,12 +53,8 @@ impl Plugin for PlayerPlugin {
             ).run_if(in_state(GameStates::Playing))
                 .run_if(in_state(AppStates::Game)))
 
-            .add_systems(OnEnter(AppStates::Game), (
-                spawn_player
-            ))
-            .add_systems(OnExit(AppStates::Game), (
-                detach_camera_from_player
-            ))
+            .add_systems(OnEnter(AppStates::Game), spawn_player)
+            .add_systems(OnExit(AppStates::Game), detach_camera_from_player)
             .add_systems(OnEnter(AppStates::GameOver), reset_player)
             .add_systems(OnEnter(AppStates::Victory), reset_player)
             .add_systems(OnEnter(AppStates::MainMenu), reset_player);
@@ -129,14 +144,15 @@ fn player_angular_movement(
 }
 
 fn player_input(
-    mut query: Query<(&ActionState<PlayerAction>, &mut PlayerInput, &mut Cannon)>,
+    mut query: Query<(&ActionState<PlayerAction>, &mut PlayerInput, &mut Trigger, &mut Arsenal, &mut MissileLauncher)>,
 ){
-    let (input_state, mut player_input, mut cannon) = query.single_mut();
+    let (input_state, mut player_input, mut trigger, mut arsenal, mut launcher) = query.single_mut();
 
     if !player_input.enabled {
         player_input.direction = Vec3::ZERO;
         player_input.rotation = Vec3::ZERO;
-        cannon.0 = false;
+        trigger.0 = false;
+        launcher.trigger = false;
         return;
     }
     let mut direction = Vec3::ZERO;
@@ -163,7 +179,19 @@ fn player_input(
     }
     player_input.rotation = rotation;
 
-    cannon.0 = input_state.pressed(PlayerAction::Shoot);
+    trigger.0 = input_state.pressed(PlayerAction::Shoot);
+    launcher.trigger = input_state.pressed(PlayerAction::FireMissile);
+    if input_state.just_pressed(PlayerAction::NextWeapon) {
+        arsenal.select_next();
+    }
+    if input_state.just_pressed(PlayerAction::PreviousWeapon) {
+        arsenal.select_previous();
+    }
+    if input_state.just_pressed(PlayerAction::Reload) {
+        if let Some(ammo) = arsenal.current_mut().ammo.as_mut() {
+            ammo.start_reload();
+        }
+    }
 }
 
 fn player_death(
@@ -196,6 +224,87 @@ fn reset_player(
 
 
 
+// Cannon first, the rest are reached with the mouse wheel
+fn player_arsenal() -> Arsenal {
+    Arsenal::new(vec![
+        Weapon {
+            name: "Cannon",
+            kind: WeaponKind::Cannon,
+            options: WeaponOptions {
+                rate: 0.1,
+                speed: 10.0,
+                power: 1.0,
+                kind: DamageKind::Kinetic,
+                falloff: Falloff::Distance { start: 15.0, end: 30.0, min: 0.5 },
+                crit: Crit { chance: 0.1, multiplier: 2.0 },
+                pierce: 0
+            },
+            heat: Some(Heat::new(5.0, 25.0)),
+            ammo: None
+        },
+        Weapon {
+            name: "Laser",
+            kind: WeaponKind::Laser { range: 40.0 },
+            options: WeaponOptions {
+                rate: 0.05,
+                speed: 0.0,
+                power: 0.4,
+                kind: DamageKind::Thermal,
+                falloff: Falloff::Distance { start: 10.0, end: 40.0, min: 0.25 },
+                crit: Crit::NONE,
+                pierce: 0
+            },
+            heat: Some(Heat::new(2.0, 20.0)),
+            ammo: None
+        },
+        Weapon {
+            name: "Shotgun",
+            kind: WeaponKind::Shotgun { pellets: 8, spread: 0.3 },
+            options: WeaponOptions {
+                rate: 0.8,
+                speed: 12.0,
+                power: 0.6,
+                kind: DamageKind::Kinetic,
+                falloff: Falloff::Age { start: 0.3, end: 1.0, min: 0.1 },
+                crit: Crit { chance: 0.05, multiplier: 2.0 },
+                pierce: 0
+            },
+            heat: None,
+            ammo: Some(Ammo::new(6, 30, 2.0))
+        },
+        Weapon {
+            name: "Railgun",
+            kind: WeaponKind::Railgun { charge_seconds: 1.5 },
+            options: WeaponOptions {
+                rate: 1.0,
+                speed: 40.0,
+                power: 8.0,
+                kind: DamageKind::Kinetic,
+                falloff: Falloff::None,
+                crit: Crit { chance: 0.25, multiplier: 1.5 },
+                pierce: 3
+            },
+            heat: Some(Heat::new(40.0, 15.0)),
+            ammo: Some(Ammo::new(4, 12, 2.5))
+        },
+        Weapon {
+            name: "Mines",
+            kind: WeaponKind::MineLayer { arm_seconds: 1.0, trigger_radius: 3.0, blast_radius: 6.0 },
+            options: WeaponOptions {
+                rate: 1.5,
+                speed: 1.0,
+                power: 10.0,
+                kind: DamageKind::Explosive,
+                falloff: Falloff::None,
+                crit: Crit::NONE,
+                pierce: 0
+            },
+            heat: None,
+            ammo: Some(Ammo::new(4, 8, 3.0))
+        }
+    ])
+}
+
 fn spawn_player(
     mut commands: Commands,
     assets: Res<AssetServer>,
@@ -223,6 +332,7 @@ fn spawn_player(
         ExternalTorque::default(),
         LinearVelocity::default(),
         AngularVelocity::default(),
+        ImpactVelocity::default(),
         Player,
         Health {
             full: 100.0,
@@ -234,23 +344,38 @@ fn spawn_player(
         },
         PlayerInput::default(),
         WeaponBundle {
-            options: WeaponOptions {
-                rate: 0.1,
-                speed: 10.0,
-                power: 1.0
-            },
+            arsenal: player_arsenal(),
             next_shot: NextShot(time.elapsed_seconds() + 0.5),
             ..default()
         },
-        SpawnArea {
-            radius: 10,
-            scale: 10
-        },
-        PreviousSpawnUpdate(MapAddress{
-            x: 1024, y: 1024, z: 1024,
-        }),
+        MapSpawnerBundle::new(10),
     )).id();
-    commands.entity(player).insert(Score::default());
+    commands.entity(player).insert((
+        Score::default(),
+        Cargo::new(CARGO_CAPACITY),
+        CollidingEntities::default(),
+        InvulnerableAfterHit(INVULNERABLE_SECONDS),
+        DamageCooldown::new(DAMAGE_COOLDOWN_SECONDS),
+        Shield::new(SHIELD_FULL, SHIELD_RECHARGE_RATE, SHIELD_RECHARGE_DELAY),
+        Knockback,
+        Faction::Player,
+        Faction::Player.ship_layers(),
+        MissileLauncher {
+            trigger: false,
+            ammo: Ammo::new(MISSILE_MAGAZINE, MISSILE_RESERVE, MISSILE_RELOAD_SECONDS),
+            rate: 0.5,
+            next_shot: 0.0,
+            lock_angle: 0.2,
+            lock_range: 60.0,
+            turn_rate: 2.5,
+            acceleration: 30.0,
+            max_speed: 40.0,
+            fuel_seconds: 5.0,
+            damage: 15.0,
+            blast_radius: 4.0
+        },
+        TargetLock::default()
+    ));
 
     commands.entity(player).push_children(&[camera]);
 
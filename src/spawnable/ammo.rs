@@ -0,0 +1,86 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use crate::spawnable::{Arsenal, MissileLauncher};
+
+
+// Rounds in the magazine, with the rest held in reserve until a reload
+#[derive(Clone)]
+pub struct Ammo {
+    pub magazine: u32,
+    pub loaded: u32,
+    pub reserve: u32,
+    reload: Timer,
+    reloading: bool
+}
+
+impl Ammo {
+    pub fn new(magazine: u32, reserve: u32, reload_seconds: f32) -> Self {
+        Self {
+            magazine,
+            loaded: magazine,
+            reserve,
+            reload: Timer::from_seconds(reload_seconds, TimerMode::Once),
+            reloading: false
+        }
+    }
+
+    pub fn can_fire(&self) -> bool {
+        !self.reloading && self.loaded > 0
+    }
+
+    pub fn is_reloading(&self) -> bool {
+        self.reloading
+    }
+
+    // Reloads on its own once the magazine runs dry
+    pub fn spend(&mut self) {
+        self.loaded = self.loaded.saturating_sub(1);
+        if self.loaded == 0 {
+            self.start_reload();
+        }
+    }
+
+    pub fn start_reload(&mut self) {
+        if self.reloading || self.loaded >= self.magazine || self.reserve == 0 {
+            return;
+        }
+        self.reloading = true;
+        self.reload.reset();
+    }
+
+    pub fn refill(&mut self, rounds: u32) {
+        self.reserve += rounds;
+        if self.loaded == 0 {
+            self.start_reload();
+        }
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        if !self.reloading {
+            return;
+        }
+        self.reload.tick(delta);
+        if self.reload.finished() {
+            let rounds = (self.magazine - self.loaded).min(self.reserve);
+            self.loaded += rounds;
+            self.reserve -= rounds;
+            self.reloading = false;
+        }
+    }
+}
+
+// Only the weapon in hand reloads, switching away puts the reload on hold
+pub fn reload_weapons(
+    time: Res<Time>,
+    mut arsenals: Query<&mut Arsenal>,
+    mut launchers: Query<&mut MissileLauncher>
+){
+    for mut arsenal in &mut arsenals {
+        if let Some(ammo) = arsenal.current_mut().ammo.as_mut() {
+            ammo.tick(time.delta());
+        }
+    }
+    for mut launcher in &mut launchers {
+        launcher.ammo.tick(time.delta());
+    }
+}
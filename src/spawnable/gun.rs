@@ -1,68 +1,277 @@
+use std::collections::HashSet;
 use std::time::Duration;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_xpbd_3d::prelude::*;
-use crate::components::{DamageEvent, Owner};
-use crate::spawnable::{Bullet, Cannon, NextShot, SpawnableHandles, WeaponBundle, WeaponOptions};
-use crate::util::{Lifetime};
+use crate::arena::impact::ImpactVelocity;
+use crate::components::{DamageEvent, HitInfo, Owner};
+use crate::components::faction::{Faction, FriendlyFire, may_damage};
+use crate::spawnable::{Arsenal, Bullet, Charge, LastPosition, Mine, NextShot, Pierce, SpawnableHandles, Trigger, WeaponKind};
+use crate::spawnable::laser::fire_laser;
+use crate::spawnable::payload::{DamagePayload, LaunchPoint};
+use crate::spawnable::pool::{Pooled, ProjectilePool};
+use crate::util::{Lifetime, started_contacts};
 
+const BULLET_LIFETIME: Duration = Duration::from_secs(3);
+const MINE_LIFETIME: Duration = Duration::from_secs(30);
+// Below this much charge a released railgun fizzles instead of firing
+const MIN_RAILGUN_CHARGE: f32 = 0.1;
+
+
+type ShooterQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a Trigger, &'a mut Arsenal, &'a Transform, &'a LinearVelocity, &'a mut NextShot, &'a mut Charge, Option<&'a Faction>)
+>;
+
+// Everything a weapon needs to put a shot into the world
+#[derive(SystemParam)]
+pub struct ShotContext<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    pub handles: Query<'w, 's, &'static SpawnableHandles>,
+    pub pool: ResMut<'w, ProjectilePool>,
+    pub spatial_query: SpatialQuery<'w, 's>,
+    pub factions: Query<'w, 's, &'static Faction>,
+    pub friendly_fire: Res<'w, FriendlyFire>,
+    pub damage_event: EventWriter<'w, DamageEvent>
+}
 
 pub fn shoot_weapons(
     time: Res<Time>,
-    handle_query: Query<&SpawnableHandles>,
-    mut commands: Commands,
-    mut query: Query<(Entity, &Cannon, &WeaponOptions, &Transform, &LinearVelocity, &mut NextShot)>
+    mut context: ShotContext,
+    mut query: ShooterQuery
 ){
     let now = time.elapsed_seconds();
-    let handles = handle_query.single();
-    for (entity, cannon_enabled, options, weapon_transform, weapon_velocity, mut next) in &mut query {
-        if !cannon_enabled.0 || next.0 > now {
+    let mut rng = fastrand::Rng::new();
+    for (entity, trigger, mut arsenal, weapon_transform, weapon_velocity, mut next, mut charge, faction) in &mut query {
+        let weapon = arsenal.current();
+        let (kind, options, ready) = (weapon.kind, weapon.options, weapon.ready());
+        let forward = weapon_transform.forward();
+        let spawn_position = weapon_transform.translation + forward;
+        let shot = Shot {
+            owner: entity,
+            faction: faction.copied(),
+            layers: faction.map(|faction| faction.shot_layers(&context.friendly_fire))
+        };
+
+        // The railgun fires when the trigger is let go, scaled by how long it charged, everything else while it is held
+        let mut payload = options.payload();
+        let firing = match kind {
+            WeaponKind::Railgun { charge_seconds } => {
+                // Only charges while a shot could go out
+                if trigger.0 {
+                    if ready && next.0 <= now {
+                        charge.0 = (charge.0 + time.delta_seconds()).min(charge_seconds);
+                    }
+                    continue;
+                }
+                // Let go while reloading or overheated, the charge is held and goes out once the weapon is ready
+                if !ready || next.0 > now {
+                    continue;
+                }
+                let fraction = charge.0 / charge_seconds;
+                charge.0 = 0.0;
+                payload.damage *= fraction;
+                fraction >= MIN_RAILGUN_CHARGE
+            },
+            _ => {
+                charge.0 = 0.0;
+                trigger.0
+            }
+        };
+        if !firing || next.0 > now || !ready {
             continue;
         }
 
-        let spawn_position = weapon_transform.translation + weapon_transform.forward();
-        let spawn_velocity = (weapon_transform.forward() * options.speed) + weapon_velocity.0;
-        commands.spawn((PbrBundle {
-            mesh: handles.mesh.clone(),
-            material: handles.material.clone(),
-            ..default()
-        }, Bullet,
+        match kind {
+            WeaponKind::Cannon | WeaponKind::Railgun { .. } => {
+                shot.fire_bullet(&mut context, spawn_position, forward * options.speed + weapon_velocity.0, options.power, options.pierce, payload);
+            },
+            WeaponKind::Laser { range } => {
+                fire_laser(&mut context, &mut rng, &shot, spawn_position, forward, range, payload);
+            },
+            WeaponKind::Shotgun { pellets, spread } => {
+                let (right, up) = (weapon_transform.right(), weapon_transform.up());
+                for _ in 0..pellets {
+                    let direction = Quat::from_axis_angle(up, (rng.f32() - 0.5) * spread)
+                        * Quat::from_axis_angle(right, (rng.f32() - 0.5) * spread)
+                        * forward;
+                    shot.fire_bullet(&mut context, spawn_position, direction * options.speed + weapon_velocity.0, options.power, options.pierce, payload);
+                }
+            },
+            WeaponKind::MineLayer { arm_seconds, trigger_radius, blast_radius } => {
+                shot.spawn_mine(&mut context, weapon_transform.translation - forward * 2.0, -forward * options.speed, payload, Mine {
+                    arm: Timer::from_seconds(arm_seconds, TimerMode::Once),
+                    trigger_radius,
+                    blast_radius
+                });
+            }
+        }
+
+        arsenal.current_mut().fired();
+        next.0 = now + options.rate;
+    }
+}
+
+// Who fired, so shots keep the shooter's side and never hit the shooter
+pub struct Shot {
+    pub owner: Entity,
+    pub faction: Option<Faction>,
+    pub layers: Option<CollisionLayers>
+}
+
+impl Shot {
+    // Bullets come out of the pool, so everything a previous shot left behind is overwritten here
+    fn fire_bullet(
+        &self,
+        context: &mut ShotContext,
+        position: Vec3,
+        velocity: Vec3,
+        mass: f32,
+        pierce: u32,
+        payload: DamagePayload
+    ){
+        let commands = &mut context.commands;
+        let bullet = context.pool.take(commands, context.handles.single());
+        commands.entity(bullet).insert((
+            Visibility::Inherited,
             Lifetime {
-                timer: Timer::new(Duration::from_secs(3), TimerMode::Once)
+                timer: Timer::new(BULLET_LIFETIME, TimerMode::Once)
             },
-            Collider::cuboid(0.1, 0.1, 0.1),
-            Mass(options.power),
-            RigidBody::Dynamic,
-            Position(spawn_position),
-            LinearVelocity(spawn_velocity),
-            Owner(entity)
+            Mass(mass),
+            Position(position),
+            Rotation::default(),
+            LinearVelocity(velocity),
+            AngularVelocity::ZERO,
+            ImpactVelocity(velocity),
+            Owner(self.owner),
+            payload,
+            LaunchPoint(position),
+            LastPosition(position),
+            Pierce {
+                remaining: pierce,
+                hit: Vec::new()
+            },
+            self.layers.unwrap_or_default()
         ));
+        match self.faction {
+            Some(faction) => commands.entity(bullet).insert(faction),
+            None => commands.entity(bullet).remove::<Faction>()
+        };
+        // Piercing bullets must not bounce off the first thing they go through
+        if pierce > 0 {
+            commands.entity(bullet).insert(Sensor);
+        } else {
+            commands.entity(bullet).remove::<Sensor>();
+        }
+    }
 
-        next.0 = now + options.rate;
+    fn spawn_mine(
+        &self,
+        context: &mut ShotContext,
+        position: Vec3,
+        velocity: Vec3,
+        payload: DamagePayload,
+        mine: Mine
+    ){
+        let handles = context.handles.single();
+        let commands = &mut context.commands;
+        let mine = commands.spawn((PbrBundle {
+            mesh: handles.mine_mesh.clone(),
+            material: handles.mine_material.clone(),
+            ..default()
+        },
+            mine,
+            Lifetime {
+                timer: Timer::new(MINE_LIFETIME, TimerMode::Once)
+            },
+            RigidBody::Kinematic,
+            Position(position),
+            LinearVelocity(velocity),
+            Owner(self.owner),
+            payload
+        )).id();
+        self.insert_side(commands, mine);
+    }
+
+    pub fn insert_side(&self, commands: &mut Commands, entity: Entity) {
+        if let (Some(faction), Some(layers)) = (self.faction, self.layers) {
+            commands.entity(entity).insert((faction, layers));
+        }
     }
 }
 
-pub fn bullet_damage(
-    bullets: Query<(Entity, &Owner), With<Bullet>>,
+type LiveBulletQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a Owner, &'a Mass, &'a ImpactVelocity, &'a Position, &'a mut LastPosition, &'a CollisionLayers, &'a DamagePayload, &'a LaunchPoint, &'a Lifetime, &'a mut Pierce),
+    (With<Bullet>, Without<Pooled>)
+>;
+
+// Contacts catch what the physics step saw, the sweep catches fast bullets that would skip through a thin target within one step
+#[allow(clippy::too_many_arguments)]
+pub fn bullet_hits(
+    spatial_query: SpatialQuery,
+    factions: Query<&Faction>,
+    friendly_fire: Res<FriendlyFire>,
+    mut bullets: LiveBulletQuery,
     mut commands: Commands,
-    mut collision_event: EventReader<CollisionStarted>,
+    mut pool: ResMut<ProjectilePool>,
+    mut collision_started: EventReader<CollisionStarted>,
+    mut collisions: EventReader<Collision>,
     mut damage_event: EventWriter<DamageEvent>
 ){
-    for CollisionStarted(entity1, entity2) in collision_event.iter() {
-        if let Ok((entity, owner)) = bullets.get(*entity1) {
-            damage_event.send(DamageEvent {
-                subject: *entity2,
-                source: owner.0,
-                value: 1.0
-            });
-            commands.entity(*entity1).despawn_recursive();
+    let mut hits = Vec::new();
+    for contact in started_contacts(&mut collision_started, &mut collisions) {
+        // The contact normal points from the first entity to the second
+        hits.push((contact.entity1, contact.entity2, contact.point2, contact.normal));
+        hits.push((contact.entity2, contact.entity1, contact.point1, -contact.normal));
+    }
+    // Sweeps the stretch travelled since the last check, so what the step jumped over is still hit
+    for (bullet, owner, _, _, position, mut last, layers, _, _, _, pierce) in &mut bullets {
+        let start = std::mem::replace(&mut last.0, position.0);
+        let travelled = position.0 - start;
+        let reach = travelled.length();
+        if reach <= f32::EPSILON {
+            continue;
         }
-        if let Ok((entity, owner)) = bullets.get(*entity2) {
-            damage_event.send(DamageEvent {
-                subject: *entity1,
-                source: owner.0,
-                value: 1.0
-            });
-            commands.entity(*entity2).despawn_recursive();
+        let direction = travelled / reach;
+        let filter = SpatialQueryFilter::new()
+            .with_masks_from_bits(layers.masks_bits())
+            .without_entities(pierce.hit.iter().copied().chain([bullet, owner.0]));
+        if let Some(hit) = spatial_query.cast_ray(start, direction, reach, true, filter) {
+            hits.push((bullet, hit.entity, start + direction * hit.time_of_impact, direction));
         }
     }
-}
\ No newline at end of file
+
+    let mut rng = fastrand::Rng::new();
+    let mut consumed = HashSet::new();
+    for (bullet, subject, point, normal) in hits {
+        if consumed.contains(&bullet) {
+            continue;
+        }
+        let Ok((_, owner, mass, velocity, _, _, _, payload, launch, lifetime, mut pierce)) = bullets.get_mut(bullet) else {
+            continue;
+        };
+        if pierce.hit.contains(&subject) || !may_damage(owner.0, subject, &factions, &friendly_fire) {
+            continue;
+        }
+        let age = lifetime.timer.elapsed_secs();
+        let distance = point.distance(launch.0);
+        damage_event.send(DamageEvent {
+            subject,
+            source: owner.0,
+            value: payload.roll(age, distance, &mut rng),
+            kind: payload.kind,
+            hit: Some(HitInfo {
+                point,
+                normal,
+                impulse: mass.0 * velocity.0.dot(normal).max(0.0)
+            })
+        });
+
+        pierce.hit.push(subject);
+        if pierce.remaining == 0 {
+            consumed.insert(bullet);
+            pool.recycle(&mut commands, bullet);
+        } else {
+            pierce.remaining -= 1;
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use crate::spawnable::Arsenal;
+
+const OVERHEAT_AT: f32 = 100.0;
+
+
+// Every shot warms the weapon, at the top it locks out until it has cooled right down
+#[derive(Clone)]
+pub struct Heat {
+    pub current: f32,
+    pub per_shot: f32,
+    // Heat lost per second
+    pub cool_rate: f32,
+    pub overheated: bool
+}
+
+impl Heat {
+    pub fn new(per_shot: f32, cool_rate: f32) -> Self {
+        Self {
+            current: 0.0,
+            per_shot,
+            cool_rate,
+            overheated: false
+        }
+    }
+
+    pub fn can_fire(&self) -> bool {
+        !self.overheated
+    }
+
+    pub fn add_shot(&mut self) {
+        self.current = (self.current + self.per_shot).min(OVERHEAT_AT);
+        if self.current >= OVERHEAT_AT {
+            self.overheated = true;
+        }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        self.current / OVERHEAT_AT
+    }
+
+    fn cool(&mut self, delta: f32) {
+        self.current = (self.current - self.cool_rate * delta).max(0.0);
+        if self.current <= 0.0 {
+            self.overheated = false;
+        }
+    }
+}
+
+// Holstered weapons keep cooling too
+pub fn cool_weapons(
+    time: Res<Time>,
+    mut arsenals: Query<&mut Arsenal>
+){
+    let delta = time.delta_seconds();
+    for mut arsenal in &mut arsenals {
+        for heat in arsenal.weapons_mut().filter_map(|weapon| weapon.heat.as_mut()) {
+            heat.cool(delta);
+        }
+    }
+}
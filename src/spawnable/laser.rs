@@ -0,0 +1,63 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::components::{DamageEvent, HitInfo};
+use crate::components::faction::may_damage;
+use crate::spawnable::Beam;
+use crate::spawnable::gun::{Shot, ShotContext};
+use crate::spawnable::payload::DamagePayload;
+use crate::util::Lifetime;
+
+const BEAM_LIFETIME: Duration = Duration::from_millis(50);
+const BEAM_WIDTH: f32 = 0.03;
+
+
+// Hitscan, the first collider along the beam takes the hit in the same frame
+pub fn fire_laser(
+    context: &mut ShotContext,
+    rng: &mut fastrand::Rng,
+    shot: &Shot,
+    origin: Vec3,
+    direction: Vec3,
+    range: f32,
+    payload: DamagePayload
+){
+    let filter = SpatialQueryFilter::new()
+        .with_masks_from_bits(shot.layers.map_or(u32::MAX, |layers| layers.masks_bits()))
+        .without_entities([shot.owner]);
+    let hit = context.spatial_query.cast_ray(origin, direction, range, true, filter);
+    let length = hit.as_ref().map_or(range, |hit| hit.time_of_impact);
+
+    if let Some(hit) = hit {
+        if may_damage(shot.owner, hit.entity, &context.factions, &context.friendly_fire) {
+            context.damage_event.send(DamageEvent {
+                subject: hit.entity,
+                source: shot.owner,
+                value: payload.roll(0.0, length, rng),
+                kind: payload.kind,
+                hit: Some(HitInfo {
+                    point: origin + direction * length,
+                    normal: direction,
+                    impulse: 0.0
+                })
+            });
+        }
+    }
+
+    let handles = context.handles.single();
+    context.commands.spawn((PbrBundle {
+        mesh: handles.beam_mesh.clone(),
+        material: handles.beam_material.clone(),
+        transform: Transform {
+            translation: origin + direction * length * 0.5,
+            rotation: Quat::from_rotation_arc(Vec3::NEG_Z, direction),
+            scale: Vec3::new(BEAM_WIDTH, BEAM_WIDTH, length)
+        },
+        ..default()
+    },
+        Beam,
+        Lifetime {
+            timer: Timer::new(BEAM_LIFETIME, TimerMode::Once)
+        }
+    ));
+}
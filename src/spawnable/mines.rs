@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::components::{DamageEvent, Health, Owner};
+use crate::components::blast::Blast;
+use crate::components::faction::{Faction, FriendlyFire, may_damage};
+use crate::effects::ExplosionEvent;
+use crate::spawnable::{Mine, SpawnableHandles};
+use crate::spawnable::payload::DamagePayload;
+
+const MINE_IMPULSE_PER_DAMAGE: f32 = 0.5;
+
+
+pub fn arm_mines(
+    time: Res<Time>,
+    handle_query: Query<&SpawnableHandles>,
+    mut mines: Query<(&mut Mine, &mut Handle<StandardMaterial>)>
+){
+    let handles = handle_query.single();
+    for (mut mine, mut material) in &mut mines {
+        if mine.arm.finished() {
+            continue;
+        }
+        mine.arm.tick(time.delta());
+        if mine.arm.finished() {
+            *material = handles.armed_mine_material.clone();
+        }
+    }
+}
+
+type MineQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a Mine, &'a Transform, &'a Owner, &'a DamagePayload, Option<&'a CollisionLayers>)
+>;
+
+// Armed mines go off as soon as anything they may hurt is inside the trigger radius
+#[allow(clippy::too_many_arguments)]
+pub fn detonate_mines(
+    mut commands: Commands,
+    spatial_query: SpatialQuery,
+    factions: Query<&Faction>,
+    friendly_fire: Res<FriendlyFire>,
+    mines: MineQuery,
+    targets: Query<(Entity, &GlobalTransform), With<Health>>,
+    mut damage_event: EventWriter<DamageEvent>,
+    mut explosion_event: EventWriter<ExplosionEvent>
+){
+    for (entity, mine, transform, owner, payload, layers) in &mines {
+        if !mine.arm.finished() {
+            continue;
+        }
+        let filter = SpatialQueryFilter::new()
+            .with_masks_from_bits(layers.map_or(u32::MAX, |layers| layers.masks_bits()))
+            .without_entities([owner.0]);
+        let triggered = spatial_query.shape_intersections(&Collider::ball(mine.trigger_radius), transform.translation, Quat::IDENTITY, filter)
+            .into_iter()
+            .any(|intruder| may_damage(owner.0, intruder, &factions, &friendly_fire));
+        if !triggered {
+            continue;
+        }
+
+        explosion_event.send(ExplosionEvent {
+            position: transform.translation,
+            power: mine.blast_radius * 0.5
+        });
+        let blast = Blast {
+            position: transform.translation,
+            radius: mine.blast_radius,
+            damage: payload.damage,
+            impulse: payload.damage * MINE_IMPULSE_PER_DAMAGE
+        };
+        let targets = targets.iter()
+            .filter(|(target, _)| may_damage(owner.0, *target, &factions, &friendly_fire))
+            .map(|(target, target_transform)| (target, target_transform.translation()));
+        damage_event.send_batch(blast.damage_events(owner.0, targets));
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::components::{DamageEvent, Dead, Health, Owner};
+use crate::components::blast::Blast;
+use crate::components::faction::{Faction, FriendlyFire, may_damage};
+use crate::effects::ExplosionEvent;
+use crate::spawnable::{Missile, MissileLauncher, SpawnableHandles, TargetLock};
+use crate::spawnable::gun::Shot;
+use crate::util::started_contacts;
+
+const MISSILE_LAUNCH_SPEED: f32 = 5.0;
+const MISSILE_IMPULSE_PER_DAMAGE: f32 = 0.5;
+
+
+type LockingLauncherQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a MissileLauncher, &'a GlobalTransform, &'a mut TargetLock)
+>;
+
+type LockTargetQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a GlobalTransform),
+    (With<Health>, Without<Dead>)
+>;
+
+// Locks onto whatever sits closest to the crosshair inside the launcher's cone and range
+pub fn acquire_target_lock(
+    factions: Query<&Faction>,
+    friendly_fire: Res<FriendlyFire>,
+    mut launchers: LockingLauncherQuery,
+    targets: LockTargetQuery
+){
+    for (entity, launcher, transform, mut lock) in &mut launchers {
+        let origin = transform.translation();
+        let forward = transform.forward();
+        let nearest = targets.iter()
+            .filter(|(target, _)| may_damage(entity, *target, &factions, &friendly_fire))
+            .filter_map(|(target, target_transform)| {
+                let offset = target_transform.translation() - origin;
+                let distance = offset.length();
+                if distance > launcher.lock_range || distance <= f32::EPSILON {
+                    return None;
+                }
+                let angle = forward.angle_between(offset);
+                (angle <= launcher.lock_angle).then_some((target, angle))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(target, _)| target);
+        if lock.0 != nearest {
+            lock.0 = nearest;
+        }
+    }
+}
+
+type FiringLauncherQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a mut MissileLauncher, &'a TargetLock, &'a Transform, &'a LinearVelocity, Option<&'a Faction>)
+>;
+
+pub fn fire_missiles(
+    time: Res<Time>,
+    handle_query: Query<&SpawnableHandles>,
+    friendly_fire: Res<FriendlyFire>,
+    mut commands: Commands,
+    mut launchers: FiringLauncherQuery
+){
+    let now = time.elapsed_seconds();
+    let handles = handle_query.single();
+    for (entity, mut launcher, lock, transform, velocity, faction) in &mut launchers {
+        if !launcher.trigger || !launcher.ammo.can_fire() || launcher.next_shot > now {
+            continue;
+        }
+        let forward = transform.forward();
+        let missile = commands.spawn((PbrBundle {
+            mesh: handles.missile_mesh.clone(),
+            material: handles.missile_material.clone(),
+            ..default()
+        },
+            Missile {
+                target: lock.0,
+                fuel: Timer::from_seconds(launcher.fuel_seconds, TimerMode::Once),
+                turn_rate: launcher.turn_rate,
+                acceleration: launcher.acceleration,
+                max_speed: launcher.max_speed,
+                damage: launcher.damage,
+                blast_radius: launcher.blast_radius
+            },
+            Collider::cuboid(0.1, 0.1, 0.4),
+            Mass(0.2),
+            RigidBody::Dynamic,
+            Position(transform.translation + forward * 1.5),
+            Rotation(transform.rotation),
+            LinearVelocity(velocity.0 + forward * MISSILE_LAUNCH_SPEED),
+            Owner(entity)
+        )).id();
+        Shot {
+            owner: entity,
+            faction: faction.copied(),
+            layers: faction.map(|faction| faction.shot_layers(&friendly_fire))
+        }.insert_side(&mut commands, missile);
+
+        launcher.ammo.spend();
+        launcher.next_shot = now + launcher.rate;
+    }
+}
+
+// Turns towards the target no faster than the turn rate and speeds up until out of fuel
+pub fn steer_missiles(
+    time: Res<Time>,
+    targets: Query<&GlobalTransform>,
+    mut missiles: Query<(&mut Missile, &Position, &mut Rotation, &mut LinearVelocity, &mut AngularVelocity)>
+){
+    let delta = time.delta_seconds();
+    for (mut missile, position, mut rotation, mut velocity, mut angular_velocity) in &mut missiles {
+        missile.fuel.tick(time.delta());
+        let heading = rotation.0 * Vec3::NEG_Z;
+        let desired = missile.target
+            .and_then(|target| targets.get(target).ok())
+            .map(|target| (target.translation() - position.0).normalize_or_zero())
+            .filter(|desired| *desired != Vec3::ZERO)
+            .unwrap_or(heading);
+
+        let angle = heading.angle_between(desired);
+        let turn = Quat::from_rotation_arc(heading, desired);
+        let step = if angle <= f32::EPSILON { 1.0 } else { (missile.turn_rate * delta / angle).min(1.0) };
+        rotation.0 = (Quat::IDENTITY.slerp(turn, step) * rotation.0).normalize();
+        angular_velocity.0 = Vec3::ZERO;
+
+        let speed = (velocity.0.length() + missile.acceleration * delta).min(missile.max_speed);
+        velocity.0 = rotation.0 * Vec3::NEG_Z * speed;
+    }
+}
+
+// Missiles go off on their first hit, or wherever they are when the fuel runs out
+#[allow(clippy::too_many_arguments)]
+pub fn detonate_missiles(
+    mut commands: Commands,
+    factions: Query<&Faction>,
+    friendly_fire: Res<FriendlyFire>,
+    missiles: Query<(Entity, &Missile, &Position, &Owner)>,
+    targets: Query<(Entity, &GlobalTransform), With<Health>>,
+    mut collision_started: EventReader<CollisionStarted>,
+    mut collisions: EventReader<Collision>,
+    mut damage_event: EventWriter<DamageEvent>,
+    mut explosion_event: EventWriter<ExplosionEvent>
+){
+    let mut detonated: HashSet<Entity> = missiles.iter()
+        .filter(|(_, missile, _, _)| missile.fuel.finished())
+        .map(|(entity, _, _, _)| entity)
+        .collect();
+    for contact in started_contacts(&mut collision_started, &mut collisions) {
+        for (missile, other) in [(contact.entity1, contact.entity2), (contact.entity2, contact.entity1)] {
+            if let Ok((_, _, _, owner)) = missiles.get(missile) {
+                if may_damage(owner.0, other, &factions, &friendly_fire) {
+                    detonated.insert(missile);
+                }
+            }
+        }
+    }
+
+    for entity in detonated {
+        let Ok((_, missile, position, owner)) = missiles.get(entity) else {
+            continue;
+        };
+        explosion_event.send(ExplosionEvent {
+            position: position.0,
+            power: missile.blast_radius * 0.5
+        });
+        let blast = Blast {
+            position: position.0,
+            radius: missile.blast_radius,
+            damage: missile.damage,
+            impulse: missile.damage * MISSILE_IMPULSE_PER_DAMAGE
+        };
+        let targets = targets.iter()
+            .filter(|(target, _)| may_damage(owner.0, *target, &factions, &friendly_fire))
+            .map(|(target, target_transform)| (target, target_transform.translation()));
+        damage_event.send_batch(blast.damage_events(owner.0, targets));
+        commands.entity(entity).despawn_recursive();
+    }
+}
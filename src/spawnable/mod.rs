@@ -1,20 +1,48 @@
 use bevy::prelude::*;
-use crate::spawnable::gun::{bullet_damage, shoot_weapons};
+use crate::components::DamageKind;
+use crate::spawnable::ammo::{Ammo, reload_weapons};
+use crate::spawnable::gun::{bullet_hits, shoot_weapons};
+use crate::spawnable::heat::{Heat, cool_weapons};
+use crate::spawnable::mines::{arm_mines, detonate_mines};
+use crate::spawnable::missiles::{acquire_target_lock, detonate_missiles, fire_missiles, steer_missiles};
+use crate::spawnable::payload::{Crit, DamagePayload, Falloff};
+use crate::spawnable::pool::{empty_projectile_pool, fill_projectile_pool, recycle_expired_projectiles};
 use crate::states::{AppStates, GameStates};
 use crate::util::{clean_up, decay_after_lifetime};
 
+pub mod ammo;
 mod gun;
+pub mod heat;
+mod laser;
+mod mines;
+mod missiles;
+pub mod payload;
+mod pool;
+#[cfg(test)]
+mod tests;
 pub struct SpawnablesPlugin;
 
 impl Plugin for SpawnablesPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_systems(Startup, setup)
-            .add_systems(OnExit(AppStates::Game), clean_up::<Bullet>)
+            .add_systems(OnEnter(AppStates::Game), fill_projectile_pool)
+            .add_systems(OnExit(AppStates::Game), (empty_projectile_pool, clean_up::<Bullet>, clean_up::<Beam>, clean_up::<Mine>, clean_up::<Missile>))
             .add_systems(Update, (
-                shoot_weapons,
-                decay_after_lifetime::<Bullet>,
-                bullet_damage
+                // Bullets parked this frame have to be back in the pool before any are taken out again
+                shoot_weapons.after(recycle_expired_projectiles).after(bullet_hits),
+                cool_weapons,
+                reload_weapons,
+                recycle_expired_projectiles,
+                decay_after_lifetime::<Beam>,
+                decay_after_lifetime::<Mine>,
+                bullet_hits,
+                arm_mines,
+                detonate_mines.after(arm_mines),
+                acquire_target_lock,
+                fire_missiles.after(acquire_target_lock),
+                steer_missiles,
+                detonate_missiles.after(steer_missiles)
             ).run_if(in_state(GameStates::Playing))
                 .run_if(in_state(AppStates::Game)));
     }
@@ -25,53 +53,254 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ){
+    let mine_mesh = Mesh::try_from(shape::Icosphere {
+        radius: 0.25,
+        subdivisions: 1
+    }).expect("Mine icosphere subdivisions out of range");
     commands.spawn(SpawnableHandles {
         mesh: meshes.add(Mesh::from(shape::Cube {size: 0.1})),
         material: materials.add(Color::rgb(0.95, 0.9, 0.8).into()),
+        beam_mesh: meshes.add(Mesh::from(shape::Cube {size: 1.0})),
+        beam_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.3, 0.2),
+            emissive: Color::rgb(1.0, 0.2, 0.1),
+            unlit: true,
+            ..default()
+        }),
+        mine_mesh: meshes.add(mine_mesh),
+        mine_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.3, 0.3, 0.3),
+            metallic: 0.8,
+            ..default()
+        }),
+        armed_mine_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.8, 0.1, 0.1),
+            emissive: Color::rgb(0.6, 0.0, 0.0),
+            ..default()
+        }),
+        missile_mesh: meshes.add(Mesh::from(shape::Box::new(0.1, 0.1, 0.4))),
+        missile_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.9, 0.9, 0.9),
+            emissive: Color::rgb(0.4, 0.2, 0.0),
+            ..default()
+        })
     });
 }
 
 
+// Held down by whoever is firing
 #[derive(Component)]
-pub struct Cannon(pub bool);
+pub struct Trigger(pub bool);
 
 #[derive(Component)]
 pub struct NextShot(pub f32);
 
+// Seconds the trigger has been held for weapons that charge up
+#[derive(Component, Default)]
+pub struct Charge(pub f32);
+
 #[derive(Component)]
 pub struct Bullet;
 
+// How many more targets a bullet can pass through, and the ones it already hit so none is hit twice
+#[derive(Component)]
+pub struct Pierce {
+    remaining: u32,
+    hit: Vec<Entity>
+}
+
+// Where a bullet was when its hits were last checked, so the next check sweeps the whole way it travelled since
+#[derive(Component)]
+pub struct LastPosition(pub Vec3);
+
+// A laser shot, only there to be seen for a moment
+#[derive(Component)]
+pub struct Beam;
+
+#[derive(Component)]
+pub struct Mine {
+    arm: Timer,
+    trigger_radius: f32,
+    blast_radius: f32
+}
+
+#[derive(Component)]
+pub struct Missile {
+    target: Option<Entity>,
+    fuel: Timer,
+    turn_rate: f32,
+    acceleration: f32,
+    max_speed: f32,
+    damage: f32,
+    blast_radius: f32
+}
+
+// Secondary weapon, fires homing missiles at whatever is locked on
 #[derive(Component)]
+pub struct MissileLauncher {
+    pub trigger: bool,
+    pub ammo: Ammo,
+    pub rate: f32,
+    pub next_shot: f32,
+    // Radians either side of straight ahead a target can be locked in
+    pub lock_angle: f32,
+    pub lock_range: f32,
+    // Radians per second
+    pub turn_rate: f32,
+    pub acceleration: f32,
+    pub max_speed: f32,
+    pub fuel_seconds: f32,
+    pub damage: f32,
+    pub blast_radius: f32
+}
+
+// What the launcher would fire at right now, if anything
+#[derive(Component, Default)]
+pub struct TargetLock(pub Option<Entity>);
+
+#[derive(Clone, Copy)]
 pub struct WeaponOptions {
     pub rate: f32,
     pub speed: f32,
-    pub power: f32
+    // Damage each shot carries before falloff and crits
+    pub power: f32,
+    pub kind: DamageKind,
+    pub falloff: Falloff,
+    pub crit: Crit,
+    // Targets a shot passes through before it is used up
+    pub pierce: u32
+}
+
+impl WeaponOptions {
+    pub fn payload(&self) -> DamagePayload {
+        DamagePayload {
+            damage: self.power,
+            kind: self.kind,
+            falloff: self.falloff,
+            crit: self.crit
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum WeaponKind {
+    // One bullet per shot
+    Cannon,
+    // Hits the first thing along the beam straight away
+    Laser { range: f32 },
+    // A handful of pellets spread over a cone, in radians
+    Shotgun { pellets: u32, spread: f32 },
+    // Charges while the trigger is held and fires on release, damage scales with the charge
+    Railgun { charge_seconds: f32 },
+    // Drops a mine behind the ship that arms after a moment and goes off when something comes close
+    MineLayer { arm_seconds: f32, trigger_radius: f32, blast_radius: f32 }
+}
+
+#[derive(Clone)]
+pub struct Weapon {
+    pub name: &'static str,
+    pub kind: WeaponKind,
+    pub options: WeaponOptions,
+    // Either, both or neither can limit how long a weapon keeps firing
+    pub heat: Option<Heat>,
+    pub ammo: Option<Ammo>
+}
+
+impl Weapon {
+    pub fn ready(&self) -> bool {
+        self.heat.as_ref().map_or(true, Heat::can_fire) && self.ammo.as_ref().map_or(true, Ammo::can_fire)
+    }
+
+    pub fn fired(&mut self) {
+        if let Some(heat) = self.heat.as_mut() {
+            heat.add_shot();
+        }
+        if let Some(ammo) = self.ammo.as_mut() {
+            ammo.spend();
+        }
+    }
+}
+
+// Every weapon a ship carries, only the selected one fires
+#[derive(Component)]
+pub struct Arsenal {
+    weapons: Vec<Weapon>,
+    selected: usize
+}
+
+impl Arsenal {
+    pub fn new(weapons: Vec<Weapon>) -> Self {
+        assert!(!weapons.is_empty(), "An arsenal needs at least one weapon");
+        Self {
+            weapons,
+            selected: 0
+        }
+    }
+
+    pub fn current(&self) -> &Weapon {
+        &self.weapons[self.selected]
+    }
+
+    pub fn current_mut(&mut self) -> &mut Weapon {
+        &mut self.weapons[self.selected]
+    }
+
+    pub fn weapons_mut(&mut self) -> impl Iterator<Item = &mut Weapon> {
+        self.weapons.iter_mut()
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.weapons.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + self.weapons.len() - 1) % self.weapons.len();
+    }
 }
 
 #[derive(Bundle)]
 pub struct WeaponBundle {
-    pub weapon: Cannon,
+    pub trigger: Trigger,
     pub next_shot: NextShot,
-    pub options: WeaponOptions
+    pub charge: Charge,
+    pub arsenal: Arsenal
 }
 
 #[derive(Component)]
 pub struct SpawnableHandles {
     mesh: Handle<Mesh>,
-    material: Handle<StandardMaterial>
+    material: Handle<StandardMaterial>,
+    beam_mesh: Handle<Mesh>,
+    beam_material: Handle<StandardMaterial>,
+    mine_mesh: Handle<Mesh>,
+    mine_material: Handle<StandardMaterial>,
+    armed_mine_material: Handle<StandardMaterial>,
+    missile_mesh: Handle<Mesh>,
+    missile_material: Handle<StandardMaterial>
 }
 
 
 impl Default for WeaponBundle {
     fn default() -> Self {
         Self {
-            weapon: Cannon(false),
+            trigger: Trigger(false),
             next_shot: NextShot(0.0),
-            options: WeaponOptions {
-                rate: 1.0,
-                speed: 1.0,
-                power: 1.0
-            }
+            charge: Charge::default(),
+            arsenal: Arsenal::new(vec![Weapon {
+                name: "Cannon",
+                kind: WeaponKind::Cannon,
+                options: WeaponOptions {
+                    rate: 1.0,
+                    speed: 1.0,
+                    power: 1.0,
+                    kind: DamageKind::Kinetic,
+                    falloff: Falloff::None,
+                    crit: Crit::NONE,
+                    pierce: 0
+                },
+                heat: None,
+                ammo: None
+            }])
         }
     }
-}
\ No newline at end of file
+}
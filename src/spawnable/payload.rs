@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use crate::components::DamageKind;
+
+// How a projectile's damage drops off the longer or further it flies
+#[derive(Clone, Copy)]
+pub enum Falloff {
+    None,
+    // Full damage until `start` seconds, then down to `min` of it by `end`
+    Age { start: f32, end: f32, min: f32 },
+    // Full damage until `start` units from where it was fired, then down to `min` of it by `end`
+    Distance { start: f32, end: f32, min: f32 }
+}
+
+impl Falloff {
+    pub fn scale(&self, age: f32, distance: f32) -> f32 {
+        let (value, start, end, min) = match *self {
+            Falloff::None => return 1.0,
+            Falloff::Age { start, end, min } => (age, start, end, min),
+            Falloff::Distance { start, end, min } => (distance, start, end, min)
+        };
+        if end <= start {
+            return if value < start { 1.0 } else { min };
+        }
+        let t = ((value - start) / (end - start)).clamp(0.0, 1.0);
+        1.0 + (min - 1.0) * t
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Crit {
+    pub chance: f32,
+    pub multiplier: f32
+}
+
+impl Crit {
+    pub const NONE: Crit = Crit {
+        chance: 0.0,
+        multiplier: 1.0
+    };
+}
+
+// Damage a projectile carries, copied from the weapon that fired it so later changes to the weapon don't reach shots in flight
+#[derive(Component, Clone, Copy)]
+pub struct DamagePayload {
+    pub damage: f32,
+    pub kind: DamageKind,
+    pub falloff: Falloff,
+    pub crit: Crit
+}
+
+impl DamagePayload {
+    pub fn roll(&self, age: f32, distance: f32, rng: &mut fastrand::Rng) -> f32 {
+        let crit = if rng.f32() < self.crit.chance { self.crit.multiplier } else { 1.0 };
+        self.damage * self.falloff.scale(age, distance) * crit
+    }
+}
+
+// Where a projectile was fired from, for distance falloff
+#[derive(Component)]
+pub struct LaunchPoint(pub Vec3);
@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use crate::arena::impact::ImpactVelocity;
+use crate::spawnable::{Bullet, SpawnableHandles};
+use crate::util::Lifetime;
+
+// Enough for the fastest weapon firing for a full bullet lifetime with room to spare
+const POOL_SIZE: usize = 128;
+// Bullets parked beyond this after a burst of fire are despawned, so the pool shrinks back
+const MAX_PARKED: usize = POOL_SIZE * 2;
+const BULLET_SIZE: f32 = 0.1;
+
+
+// Bullets waiting to be fired, so sustained fire reuses entities instead of spawning and despawning them
+#[derive(Resource, Default)]
+pub struct ProjectilePool {
+    free: Vec<Entity>
+}
+
+// A parked bullet, hidden and out of the physics simulation until it is fired again
+#[derive(Component)]
+pub struct Pooled;
+
+impl ProjectilePool {
+    // Hands out a parked bullet, growing the pool if every bullet is in flight
+    pub fn take(&mut self, commands: &mut Commands, handles: &SpawnableHandles) -> Entity {
+        let bullet = self.free.pop().unwrap_or_else(|| spawn_parked_bullet(commands, handles));
+        commands.entity(bullet)
+            .remove::<Pooled>()
+            .insert((
+                RigidBody::Dynamic,
+                Collider::cuboid(BULLET_SIZE, BULLET_SIZE, BULLET_SIZE)
+            ));
+        bullet
+    }
+
+    // Safe to call more than once a frame, a bullet that hits and expires together is only parked once
+    pub fn recycle(&mut self, commands: &mut Commands, bullet: Entity) {
+        if self.free.contains(&bullet) {
+            return;
+        }
+        // Without a body or collider the physics step skips it entirely
+        commands.entity(bullet)
+            .remove::<(RigidBody, Collider)>()
+            .insert((Pooled, Visibility::Hidden));
+        self.free.push(bullet);
+    }
+
+    // Despawns what a long burst of fire left parked beyond the cap
+    fn trim(&mut self, commands: &mut Commands) {
+        if self.free.len() <= MAX_PARKED {
+            return;
+        }
+        for bullet in self.free.drain(MAX_PARKED..) {
+            commands.entity(bullet).despawn_recursive();
+        }
+    }
+}
+
+fn spawn_parked_bullet(commands: &mut Commands, handles: &SpawnableHandles) -> Entity {
+    commands.spawn((PbrBundle {
+        mesh: handles.mesh.clone(),
+        material: handles.material.clone(),
+        visibility: Visibility::Hidden,
+        ..default()
+    },
+        Bullet,
+        Pooled,
+        Lifetime {
+            timer: Timer::default()
+        },
+        ImpactVelocity::default()
+    )).id()
+}
+
+pub fn fill_projectile_pool(
+    mut commands: Commands,
+    handle_query: Query<&SpawnableHandles>
+){
+    let handles = handle_query.single();
+    let free = (0..POOL_SIZE)
+        .map(|_| spawn_parked_bullet(&mut commands, handles))
+        .collect();
+    commands.insert_resource(ProjectilePool { free });
+}
+
+// The bullets themselves go with clean_up::<Bullet>
+pub fn empty_projectile_pool(
+    mut commands: Commands
+){
+    commands.remove_resource::<ProjectilePool>();
+}
+
+type ExpiringBulletQuery<'w, 's, 'a> = Query<'w, 's,
+    (Entity, &'a mut Lifetime),
+    (With<Bullet>, Without<Pooled>)
+>;
+
+pub fn recycle_expired_projectiles(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    mut bullets: ExpiringBulletQuery
+){
+    pool.trim(&mut commands);
+    for (bullet, mut lifetime) in &mut bullets {
+        lifetime.timer.tick(time.delta());
+        if lifetime.timer.finished() {
+            pool.recycle(&mut commands, bullet);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::time::{Duration, Instant};
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::*;
+use bevy::time::TimePlugin;
+use bevy_xpbd_3d::{prelude::*, PhysicsSchedule, PhysicsStepSet};
+use crate::components::{DamageEvent, DamageKind};
+use crate::components::faction::{Faction, FriendlyFire, ignore_owner_collisions};
+use crate::spawnable::{Arsenal, Bullet, NextShot, SpawnableHandles, Trigger, Weapon, WeaponBundle, WeaponKind, WeaponOptions};
+use crate::spawnable::gun::{bullet_hits, shoot_weapons};
+use crate::spawnable::payload::{Crit, Falloff};
+use crate::spawnable::pool::{fill_projectile_pool, Pooled, recycle_expired_projectiles};
+use crate::util::Lifetime;
+
+const FRAME: Duration = Duration::from_micros(16_667);
+
+
+// Fires, expires and lands bullets headless, with a clock stepped by hand so the test doesn't wait in real time
+fn weapon_app(options: WeaponOptions) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins((
+            MinimalPlugins.build().disable::<TimePlugin>(),
+            TransformPlugin,
+            HierarchyPlugin,
+            PhysicsPlugins::default()
+        ))
+        .insert_resource(Time::new(Instant::now()))
+        .insert_resource(Gravity(Vec3::ZERO))
+        .insert_resource(FriendlyFire(false))
+        .add_event::<DamageEvent>()
+        .add_systems(Startup, fill_projectile_pool)
+        .add_systems(PhysicsSchedule, ignore_owner_collisions
+            .after(PhysicsStepSet::BroadPhase)
+            .before(PhysicsStepSet::Substeps))
+        .add_systems(Update, (
+            shoot_weapons.after(recycle_expired_projectiles).after(bullet_hits),
+            recycle_expired_projectiles,
+            bullet_hits
+        ));
+    app.world.spawn(SpawnableHandles {
+        mesh: Handle::default(),
+        material: Handle::default(),
+        beam_mesh: Handle::default(),
+        beam_material: Handle::default(),
+        mine_mesh: Handle::default(),
+        mine_material: Handle::default(),
+        armed_mine_material: Handle::default(),
+        missile_mesh: Handle::default(),
+        missile_material: Handle::default()
+    });
+    spawn_shooter(&mut app, options);
+    app
+}
+
+fn spawn_shooter(app: &mut App, options: WeaponOptions) -> Entity {
+    app.world.spawn((
+        WeaponBundle {
+            trigger: Trigger(true),
+            arsenal: Arsenal::new(vec![Weapon {
+                name: "Cannon",
+                kind: WeaponKind::Cannon,
+                options,
+                heat: None,
+                ammo: None
+            }]),
+            ..default()
+        },
+        // On a side so its bullets pass through each other
+        Faction::Player,
+        Transform::default(),
+        LinearVelocity::default()
+    )).id()
+}
+
+fn cannon(rate: f32, speed: f32, pierce: u32) -> WeaponOptions {
+    WeaponOptions {
+        rate,
+        speed,
+        power: 1.0,
+        kind: DamageKind::Kinetic,
+        falloff: Falloff::None,
+        crit: Crit::NONE,
+        pierce
+    }
+}
+
+fn firing_app(rate: f32) -> App {
+    weapon_app(cannon(rate, 10.0, 0))
+}
+
+// A wall across the line of fire, which runs down the negative z axis from the shooter
+fn spawn_wall(app: &mut App, distance: f32, thickness: f32) -> Entity {
+    app.world.spawn((
+        RigidBody::Static,
+        Collider::cuboid(4.0, 4.0, thickness),
+        TransformBundle::from_transform(Transform::from_xyz(0.0, 0.0, -distance))
+    )).id()
+}
+
+// Advances the clock by one frame and returns the entity count after it
+fn step(app: &mut App) -> u32 {
+    let mut time = app.world.resource_mut::<Time>();
+    let now = time.last_update().unwrap_or_else(|| time.startup()) + FRAME;
+    time.update_with_instant(now);
+    app.update();
+    app.world.entities().len()
+}
+
+fn fire_for(app: &mut App, seconds: f32) -> Vec<u32> {
+    let frames = (seconds / FRAME.as_secs_f32()) as u32;
+    (0..frames).map(|_| step(app)).collect()
+}
+
+// Steps the clock and gathers who took damage along the way
+fn damaged_while(app: &mut App, seconds: f32) -> Vec<Entity> {
+    let mut reader = ManualEventReader::<DamageEvent>::default();
+    let frames = (seconds / FRAME.as_secs_f32()) as u32;
+    let mut subjects = Vec::new();
+    for _ in 0..frames {
+        step(app);
+        subjects.extend(reader.iter(app.world.resource::<Events<DamageEvent>>()).map(|damage| damage.subject));
+    }
+    subjects
+}
+
+fn bullets_in_flight(app: &mut App) -> Vec<Entity> {
+    app.world.query_filtered::<Entity, (With<Bullet>, Without<Pooled>)>().iter(&app.world).collect()
+}
+
+#[test]
+fn sustained_fire_does_not_spawn_entities() {
+    let mut app = firing_app(0.1);
+    let counts = fire_for(&mut app, 10.0);
+
+    assert!(!bullets_in_flight(&mut app).is_empty());
+    assert!(counts.iter().all(|count| *count == counts[0]), "entity count changed during sustained fire");
+}
+
+#[test]
+fn exhausted_pool_grows_then_stays_flat() {
+    // More bullets in flight than the pool starts with
+    let mut app = firing_app(0.01);
+    let warm_up = fire_for(&mut app, 3.5);
+    let counts = fire_for(&mut app, 5.0);
+
+    assert!(counts[0] > warm_up[0]);
+    assert!(counts.iter().all(|count| *count == counts[0]), "entity count changed once the pool had grown");
+}
+
+#[test]
+fn bullet_expiring_as_the_weapon_fires_is_reused() {
+    // Slow enough that only the shots let through below are fired
+    let mut app = firing_app(100.0);
+    step(&mut app);
+    let bullet = bullets_in_flight(&mut app)[0];
+
+    // Runs out on the next frame, the same frame the weapon fires again and takes it straight back out of the pool
+    let mut lifetime = app.world.get_mut::<Lifetime>(bullet).unwrap();
+    let duration = lifetime.timer.duration();
+    lifetime.timer.set_elapsed(duration);
+    app.world.query::<&mut NextShot>().single_mut(&mut app.world).0 = 0.0;
+    step(&mut app);
+
+    assert_eq!(bullets_in_flight(&mut app), vec![bullet]);
+    assert_eq!(app.world.get::<Visibility>(bullet), Some(&Visibility::Inherited));
+}
+
+#[test]
+fn bullet_without_pierce_is_recycled_after_one_hit() {
+    let mut app = weapon_app(cannon(100.0, 10.0, 0));
+    let near = spawn_wall(&mut app, 4.0, 0.5);
+    spawn_wall(&mut app, 8.0, 0.5);
+
+    assert_eq!(damaged_while(&mut app, 1.5), vec![near]);
+    assert!(bullets_in_flight(&mut app).is_empty());
+}
+
+#[test]
+fn piercing_bullet_hits_each_target_once() {
+    let mut app = weapon_app(cannon(100.0, 10.0, 2));
+    let walls: Vec<Entity> = [3.0, 5.0, 7.0, 9.0].into_iter()
+        .map(|distance| spawn_wall(&mut app, distance, 0.5))
+        .collect();
+
+    assert_eq!(damaged_while(&mut app, 1.5), walls[..3]);
+    assert!(bullets_in_flight(&mut app).is_empty());
+}
+
+#[test]
+fn fast_bullet_hits_thin_target() {
+    // Covers ten units a frame, far more than the wall and the bullet are thick
+    let mut app = weapon_app(cannon(100.0, 600.0, 0));
+    let wall = spawn_wall(&mut app, 20.0, 0.05);
+
+    assert_eq!(damaged_while(&mut app, 0.5), vec![wall]);
+}
+
+#[test]
+fn own_bullets_pass_through_the_shooter_with_friendly_fire() {
+    let mut app = weapon_app(cannon(100.0, 10.0, 0));
+    app.insert_resource(FriendlyFire(true));
+    // A hull big enough that bullets spawn inside it
+    let shooter = app.world.query_filtered::<Entity, With<Trigger>>().single(&app.world);
+    app.world.entity_mut(shooter).insert((
+        RigidBody::Dynamic,
+        Collider::cuboid(4.0, 4.0, 4.0),
+        Faction::Player.ship_layers()
+    ));
+
+    assert!(damaged_while(&mut app, 0.5).is_empty());
+    assert_eq!(bullets_in_flight(&mut app).len(), 1);
+    assert_eq!(app.world.get::<LinearVelocity>(shooter).unwrap().0, Vec3::ZERO);
+}
+
+#[test]
+fn parked_bullets_leave_the_simulation_and_the_pool_shrinks_back() {
+    let mut app = firing_app(0.001);
+    // Enough fire between them to leave more bullets than the pool keeps once it all lands
+    spawn_shooter(&mut app, cannon(0.001, 10.0, 0));
+    spawn_shooter(&mut app, cannon(0.001, 10.0, 0));
+    fire_for(&mut app, 4.0);
+    let in_flight = bullets_in_flight(&mut app).len();
+
+    for mut trigger in app.world.query::<&mut Trigger>().iter_mut(&mut app.world) {
+        trigger.0 = false;
+    }
+    fire_for(&mut app, 5.0);
+
+    let parked = app.world.query_filtered::<Entity, With<Pooled>>().iter(&app.world).count();
+    let simulated = app.world.query_filtered::<Entity, (With<Pooled>, Or<(With<RigidBody>, With<Collider>)>)>()
+        .iter(&app.world)
+        .count();
+    assert!(bullets_in_flight(&mut app).is_empty());
+    assert!(parked < in_flight, "{parked} bullets parked after {in_flight} were in flight");
+    assert_eq!(simulated, 0);
+}
@@ -1,13 +1,22 @@
 use bevy::prelude::*;
-use crate::ui::{FONT_PATH, MenuButtonAction, NORMAL_BUTTON, PANEL_BACKGROUND};
+use crate::arena::seed::WorldSeed;
+use crate::ui::{FONT_PATH, MenuButtonAction, NORMAL_BUTTON, PANEL_BACKGROUND, seed_label, SeedText};
 
 #[derive(Component)]
 pub struct MainMenuUi;
 
+// The first digit typed on the menu replaces the shown seed, later ones are appended to it
+#[derive(Resource, Default)]
+pub struct SeedEntry {
+    typed: Option<u32>
+}
+
 pub fn setup_main_menu(
     mut commands: Commands,
-    asset_server: Res<AssetServer>
+    asset_server: Res<AssetServer>,
+    seed: Res<WorldSeed>
 ){
+    commands.insert_resource(SeedEntry::default());
     commands
         // Menu Background
         .spawn((NodeBundle {
@@ -41,6 +50,23 @@ pub fn setup_main_menu(
                     }),
                 Label,
             ));
+            // Seed text
+            parent.spawn((
+                TextBundle::from_section(
+                    seed_label(&seed),
+                    TextStyle {
+                        font: asset_server.load(FONT_PATH),
+                        font_size: 10.0,
+                        color: Color::WHITE,
+                    },
+                )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(5.)),
+                        ..default()
+                    }),
+                Label,
+                SeedText,
+            ));
             // Play
             parent
                 .spawn((ButtonBundle {
@@ -67,6 +93,32 @@ pub fn setup_main_menu(
                     }));
                 });
 
+            // Random Seed Button
+            parent
+                .spawn((ButtonBundle {
+                    style: Style {
+                        width: Val::Px(150.0),
+                        height: Val::Px(65.0),
+                        border: UiRect::all(Val::Px(5.)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::vertical(Val::Px(10.)),
+                        ..default()
+                    },
+                    border_color: BorderColor(Color::BLACK),
+                    background_color: NORMAL_BUTTON.into(),
+                    ..default()
+                },
+                        MenuButtonAction::RandomSeed
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section("Random Seed", TextStyle {
+                        font: asset_server.load(FONT_PATH),
+                        font_size: 10.0,
+                        color: Color::rgb(0.9, 0.9, 0.9)
+                    }));
+                });
+
             // Quit Button
             parent
                 .spawn((ButtonBundle {
@@ -93,4 +145,37 @@ pub fn setup_main_menu(
                     }));
                 });
         });
-}
\ No newline at end of file
+}
+
+// Type digits on the main menu to enter a seed, backspace to remove the last digit
+pub fn edit_seed_input(
+    mut characters: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+    mut entry: ResMut<SeedEntry>,
+    mut seed: ResMut<WorldSeed>
+){
+    let mut value = seed.0;
+    // Still typing only while the seed is the one last typed, so a reroll starts over too
+    let mut typing = entry.typed == Some(seed.0);
+    for character in characters.iter() {
+        if let Some(digit) = character.char.to_digit(10) {
+            if !typing {
+                value = 0;
+                typing = true;
+            }
+            value = value.checked_mul(10)
+                .and_then(|shifted| shifted.checked_add(digit))
+                .unwrap_or(value);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        value /= 10;
+        typing = true;
+    }
+    if typing {
+        entry.typed = Some(value);
+    }
+    if value != seed.0 {
+        seed.0 = value;
+    }
+}
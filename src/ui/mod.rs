@@ -1,9 +1,10 @@
 use bevy::app::AppExit;
 use bevy::prelude::*;
+use crate::arena::seed::WorldSeed;
 use crate::states::{AppStates, GameStates};
 use crate::ui::game_over::{GameOverUi, setup_game_over_ui};
-use crate::ui::main_menu::{MainMenuUi, setup_main_menu};
-use crate::ui::overlay::{GameOverlayUi, setup_overlay_ui, update_health_overlay_text, update_points_overlay_text};
+use crate::ui::main_menu::{edit_seed_input, MainMenuUi, setup_main_menu};
+use crate::ui::overlay::{GameOverlayUi, setup_overlay_ui, update_biome_overlay_text, update_cargo_overlay_text, update_health_overlay_text, update_hit_indicator, update_points_overlay_text, update_shield_overlay_bar, update_weapon_overlay_text, update_missile_overlay_text, update_lock_indicator};
 use crate::ui::pause_menu::{open_pause_menu, PauseMenuUi};
 use crate::ui::victory::{setup_victory_ui, VictoryUi};
 
@@ -13,6 +14,8 @@ mod game_over;
 mod victory;
 mod overlay;
 mod window;
+#[cfg(test)]
+mod tests;
 
 pub struct UiPlugin;
 
@@ -21,11 +24,13 @@ impl Plugin for UiPlugin {
         app
             .add_systems(Update, (
                 button_interaction_effects,
-                menu_action
+                menu_action,
+                update_seed_text.run_if(resource_exists_and_changed::<WorldSeed>())
             ));
 
         app
             .add_systems(OnEnter(AppStates::MainMenu), setup_main_menu)
+            .add_systems(Update, edit_seed_input.run_if(in_state(AppStates::MainMenu)))
             .add_systems(OnExit(AppStates::MainMenu), close_panel::<MainMenuUi>);
 
         app.add_systems(
@@ -48,7 +53,7 @@ impl Plugin for UiPlugin {
 
         app
             .add_systems(OnEnter(AppStates::Game), setup_overlay_ui)
-            .add_systems(Update, (update_health_overlay_text, update_points_overlay_text).run_if(in_state(AppStates::Game)))
+            .add_systems(Update, (update_health_overlay_text, update_points_overlay_text, update_biome_overlay_text, update_cargo_overlay_text, update_shield_overlay_bar, update_weapon_overlay_text, update_missile_overlay_text, update_lock_indicator, update_hit_indicator).run_if(in_state(AppStates::Game)))
             .add_systems(OnExit(AppStates::Game), close_panel::<GameOverlayUi>);
 
     }
@@ -60,14 +65,45 @@ const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
 const PANEL_BACKGROUND: Color = Color::rgba(0.75, 0.75, 1.0, 0.25);
 const FONT_PATH: &str = "fonts/dogica/OTF/dogica.otf";
 
+type ButtonInteractionQuery<'w, 's, 'a> = Query<'w, 's,
+    (
+        &'a Interaction,
+        &'a mut BackgroundColor,
+        &'a mut BorderColor,
+    ),
+    (Changed<Interaction>, With<Button>)
+>;
+
+type MenuActionQuery<'w, 's, 'a> = Query<'w, 's,
+    (&'a Interaction, &'a MenuButtonAction),
+    (Changed<Interaction>, With<Button>)
+>;
+
 #[derive(Component)]
 enum MenuButtonAction {
     Play,
     MainMenu,
     Resume,
+    RandomSeed,
     Quit
 }
 
+#[derive(Component)]
+struct SeedText;
+
+fn seed_label(seed: &WorldSeed) -> String {
+    format!("Seed: {0}", seed.0)
+}
+
+fn update_seed_text(
+    seed: Res<WorldSeed>,
+    mut text_query: Query<&mut Text, With<SeedText>>
+){
+    for mut text in &mut text_query {
+        text.sections[0].value = seed_label(&seed);
+    }
+}
+
 fn close_panel<T:Component>(
     mut commands: Commands,
     query: Query<Entity, With<T>>
@@ -78,17 +114,9 @@ fn close_panel<T:Component>(
 }
 
 fn button_interaction_effects(
-    mut interaction_query: Query<
-        (
-            &Interaction,
-            &mut BackgroundColor,
-            &mut BorderColor,
-            &Children,
-        ),
-        (Changed<Interaction>, With<Button>)
-    >
+    mut interaction_query: ButtonInteractionQuery
 ){
-    for (interaction, mut color, mut border_color, children) in &mut interaction_query {
+    for (interaction, mut color, mut border_color) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
                 *color = PRESSED_BUTTON.into();
@@ -107,11 +135,9 @@ fn button_interaction_effects(
 }
 
 fn menu_action(
-    interaction_query: Query<
-        (&Interaction, &MenuButtonAction),
-        (Changed<Interaction>, With<Button>)
-    >,
+    interaction_query: MenuActionQuery,
     mut app_exit_events: EventWriter<AppExit>,
+    mut seed: ResMut<WorldSeed>,
     mut game_state: ResMut<NextState<GameStates>>,
     mut app_state: ResMut<NextState<AppStates>>
 ){
@@ -128,6 +154,9 @@ fn menu_action(
                 },
                 MenuButtonAction::Resume => {
                     game_state.set(GameStates::Playing);
+                },
+                MenuButtonAction::RandomSeed => {
+                    *seed = WorldSeed::random();
                 }
             }
         }
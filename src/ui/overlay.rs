@@ -1,7 +1,13 @@
 use bevy::prelude::*;
-use crate::components::{Health, Score};
+use crate::arena::biomes::BiomeMap;
+use crate::arena::generation::translation_to_address;
+use crate::components::{DamageEvent, Health, Score};
+use crate::components::cargo::{Cargo, Ore};
+use crate::components::shield::Shield;
 use crate::player::Player;
-use crate::ui::{FONT_PATH, MenuButtonAction, NORMAL_BUTTON, PANEL_BACKGROUND};
+use crate::spawnable::{Arsenal, Charge, MissileLauncher, TargetLock, WeaponKind};
+use crate::spawnable::ammo::Ammo;
+use crate::ui::FONT_PATH;
 
 #[derive(Component)]
 pub struct GameOverlayUi;
@@ -12,10 +18,45 @@ pub struct HealthText;
 #[derive(Component)]
 pub struct PointText;
 
+// Flashes around the centre of the screen on the side the player was hit from
+#[derive(Component)]
+pub struct HitIndicator {
+    timer: Timer
+}
+
+// Fill of the shield bar, its width follows the shield's charge
+#[derive(Component)]
+pub struct ShieldBar;
+
+#[derive(Component)]
+pub struct BiomeText;
+
+#[derive(Component)]
+pub struct CargoText;
+
+#[derive(Component)]
+pub struct WeaponText;
+
+#[derive(Component)]
+pub struct MissileText;
+
+// Box drawn around whatever the missiles are locked onto
+#[derive(Component)]
+pub struct LockIndicator;
+
+const HIT_INDICATOR_SECONDS: f32 = 0.6;
+const LOCK_INDICATOR_SIZE: f32 = 24.0;
+// Distance from the centre of the screen, in percent of its size
+const HIT_INDICATOR_RADIUS: f32 = 12.0;
+
 pub fn setup_overlay_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>
 ){
+    // Starts run out so nothing shows until the first hit
+    let mut hidden_indicator_timer = Timer::from_seconds(HIT_INDICATOR_SECONDS, TimerMode::Once);
+    hidden_indicator_timer.tick(hidden_indicator_timer.duration());
+
     commands
         // Overlay container
         .spawn((NodeBundle {
@@ -33,6 +74,36 @@ pub fn setup_overlay_ui(
                 GameOverlayUi
         ))
         .with_children(|parent| {
+            // Hit indicator
+            parent.spawn((NodeBundle {
+                style: Style {
+                    width: Val::Px(10.),
+                    height: Val::Px(10.),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+                HitIndicator {
+                    timer: hidden_indicator_timer
+                }
+            ));
+            // Lock indicator
+            parent.spawn((NodeBundle {
+                style: Style {
+                    width: Val::Px(LOCK_INDICATOR_SIZE),
+                    height: Val::Px(LOCK_INDICATOR_SIZE),
+                    border: UiRect::all(Val::Px(2.)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                border_color: Color::rgb(1.0, 0.3, 0.2).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+                LockIndicator
+            ));
             // Health text
             parent.spawn((
                 TextBundle::from_section(
@@ -50,6 +121,29 @@ pub fn setup_overlay_ui(
                 Label,
                 HealthText,
             ));
+            // Shield bar
+            parent.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(100.),
+                    height: Val::Px(6.),
+                    margin: UiRect::all(Val::Px(5.)),
+                    ..default()
+                },
+                background_color: Color::rgb(0.1, 0.15, 0.25).into(),
+                ..default()
+            }).with_children(|bar| {
+                bar.spawn((NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.3, 0.7, 1.0).into(),
+                    ..default()
+                },
+                    ShieldBar
+                ));
+            });
             // Points text
             parent.spawn((
                 TextBundle::from_section(
@@ -67,6 +161,74 @@ pub fn setup_overlay_ui(
                 Label,
                 PointText,
             ));
+            // Biome text
+            parent.spawn((
+                TextBundle::from_section(
+                    "Biome:",
+                    TextStyle {
+                        font: asset_server.load(FONT_PATH),
+                        font_size: 10.0,
+                        color: Color::WHITE,
+                    },
+                )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(5.)),
+                        ..default()
+                    }),
+                Label,
+                BiomeText,
+            ));
+            // Cargo text
+            parent.spawn((
+                TextBundle::from_section(
+                    "Cargo:",
+                    TextStyle {
+                        font: asset_server.load(FONT_PATH),
+                        font_size: 10.0,
+                        color: Color::WHITE,
+                    },
+                )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(5.)),
+                        ..default()
+                    }),
+                Label,
+                CargoText,
+            ));
+            // Weapon text
+            parent.spawn((
+                TextBundle::from_section(
+                    "Weapon:",
+                    TextStyle {
+                        font: asset_server.load(FONT_PATH),
+                        font_size: 10.0,
+                        color: Color::WHITE,
+                    },
+                )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(5.)),
+                        ..default()
+                    }),
+                Label,
+                WeaponText,
+            ));
+            // Missile text
+            parent.spawn((
+                TextBundle::from_section(
+                    "Missiles:",
+                    TextStyle {
+                        font: asset_server.load(FONT_PATH),
+                        font_size: 10.0,
+                        color: Color::WHITE,
+                    },
+                )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(5.)),
+                        ..default()
+                    }),
+                Label,
+                MissileText,
+            ));
         });
 }
 
@@ -88,4 +250,136 @@ pub fn update_points_overlay_text(
     let mut text = text_query.single_mut();
 
     text.sections[0].value = format!("Points: {0:?}", points.current);
-}
\ No newline at end of file
+}
+pub fn update_biome_overlay_text(
+    player_query: Query<&GlobalTransform, With<Player>>,
+    biomes: Res<BiomeMap>,
+    mut text_query: Query<&mut Text, With<BiomeText>>
+){
+    let transform = player_query.single();
+    let mut text = text_query.single_mut();
+    let biome = biomes.biome_at(&translation_to_address(transform.translation()));
+
+    text.sections[0].value = format!("Biome: {0}", biome.params().name);
+}
+
+pub fn update_cargo_overlay_text(
+    cargo_query: Query<&Cargo, (With<Player>, Changed<Cargo>)>,
+    mut text_query: Query<&mut Text, With<CargoText>>
+){
+    let Ok(cargo) = cargo_query.get_single() else {
+        return;
+    };
+    let mut text = text_query.single_mut();
+
+    let stored: String = Ore::ALL.iter()
+        .map(|ore| format!(" {0} {1}", ore.name(), cargo.amount(ore)))
+        .collect();
+    text.sections[0].value = format!("Cargo: {0} of {1} -{2}", cargo.total(), cargo.capacity, stored);
+}
+
+pub fn update_weapon_overlay_text(
+    weapon_query: Query<(&Arsenal, &Charge), With<Player>>,
+    mut text_query: Query<&mut Text, With<WeaponText>>
+){
+    let Ok((arsenal, charge)) = weapon_query.get_single() else {
+        return;
+    };
+    let mut text = text_query.single_mut();
+    let weapon = arsenal.current();
+
+    let mut status = match weapon.kind {
+        WeaponKind::Railgun { charge_seconds } => format!("Weapon: {0} {1:.0}%", weapon.name, charge.0 / charge_seconds * 100.0),
+        _ => format!("Weapon: {0}", weapon.name)
+    };
+    if let Some(ammo) = &weapon.ammo {
+        status += &ammo_status(ammo);
+    }
+    if let Some(heat) = &weapon.heat {
+        status += &format!(" - Heat {0:.0}%", heat.fraction() * 100.0);
+        if heat.overheated {
+            status += " Overheated";
+        }
+    }
+    text.sections[0].value = status;
+}
+
+pub fn update_missile_overlay_text(
+    launcher_query: Query<(&MissileLauncher, &TargetLock), With<Player>>,
+    mut text_query: Query<&mut Text, With<MissileText>>
+){
+    let Ok((launcher, lock)) = launcher_query.get_single() else {
+        return;
+    };
+    let mut text = text_query.single_mut();
+    let status = if lock.0.is_some() { " - Locked" } else { "" };
+
+    text.sections[0].value = format!("Missiles:{0}{1}", ammo_status(&launcher.ammo), status);
+}
+
+// Loaded of magazine, then what is left in reserve
+fn ammo_status(ammo: &Ammo) -> String {
+    let reloading = if ammo.is_reloading() { " Reloading" } else { "" };
+    format!(" {0}/{1} ({2}){3}", ammo.loaded, ammo.magazine, ammo.reserve, reloading)
+}
+
+pub fn update_lock_indicator(
+    lock_query: Query<&TargetLock, With<Player>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    targets: Query<&GlobalTransform>,
+    mut indicator_query: Query<(&mut Style, &mut Visibility), With<LockIndicator>>
+){
+    let (mut style, mut visibility) = indicator_query.single_mut();
+    let (Ok(lock), Ok((camera, camera_transform))) = (lock_query.get_single(), camera_query.get_single()) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let screen_position = lock.0
+        .and_then(|target| targets.get(target).ok())
+        .and_then(|target| camera.world_to_viewport(camera_transform, target.translation()));
+
+    match screen_position {
+        Some(position) => {
+            style.left = Val::Px(position.x - LOCK_INDICATOR_SIZE * 0.5);
+            style.top = Val::Px(position.y - LOCK_INDICATOR_SIZE * 0.5);
+            *visibility = Visibility::Inherited;
+        },
+        None => *visibility = Visibility::Hidden
+    }
+}
+
+pub fn update_shield_overlay_bar(
+    shield_query: Query<&Shield, With<Player>>,
+    mut bar_query: Query<&mut Style, With<ShieldBar>>
+){
+    let Ok(shield) = shield_query.get_single() else {
+        return;
+    };
+    let mut bar = bar_query.single_mut();
+
+    bar.width = Val::Percent(shield.current / shield.full * 100.0);
+}
+
+pub fn update_hit_indicator(
+    time: Res<Time>,
+    mut damage_event: EventReader<DamageEvent>,
+    player_query: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut indicator_query: Query<(&mut HitIndicator, &mut Style, &mut BackgroundColor)>
+){
+    let Ok((player, transform)) = player_query.get_single() else {
+        return;
+    };
+    let (mut indicator, mut style, mut color) = indicator_query.single_mut();
+
+    for hit in damage_event.iter().filter(|damage| damage.subject == player).filter_map(|damage| damage.hit) {
+        // The normal points into the ship, so the hit came from the other way
+        let local = transform.compute_transform().rotation.inverse() * -hit.normal;
+        let direction = Vec2::new(local.x, local.y).normalize_or_zero();
+        style.left = Val::Percent(50.0 + direction.x * HIT_INDICATOR_RADIUS);
+        style.top = Val::Percent(50.0 - direction.y * HIT_INDICATOR_RADIUS);
+        indicator.timer.reset();
+    }
+
+    indicator.timer.tick(time.delta());
+    color.0 = Color::rgba(1.0, 0.2, 0.1, indicator.timer.percent_left());
+}
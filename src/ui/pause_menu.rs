@@ -1,5 +1,6 @@
 use bevy::prelude::*;
-use crate::ui::{FONT_PATH, MenuButtonAction, NORMAL_BUTTON, PANEL_BACKGROUND};
+use crate::arena::seed::WorldSeed;
+use crate::ui::{FONT_PATH, MenuButtonAction, NORMAL_BUTTON, PANEL_BACKGROUND, seed_label, SeedText};
 
 #[derive(Component)]
 pub struct PauseMenuUi;
@@ -7,7 +8,8 @@ pub struct PauseMenuUi;
 
 pub fn open_pause_menu(
     mut commands: Commands,
-    asset_server: Res<AssetServer>
+    asset_server: Res<AssetServer>,
+    seed: Res<WorldSeed>
 ){
     commands
         // Pause Menu Background
@@ -45,6 +47,23 @@ pub fn open_pause_menu(
                 // for accessibility to treat the text accordingly.
                 Label,
             ));
+            // Seed text
+            parent.spawn((
+                TextBundle::from_section(
+                    seed_label(&seed),
+                    TextStyle {
+                        font: asset_server.load(FONT_PATH),
+                        font_size: 10.0,
+                        color: Color::WHITE,
+                    },
+                )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(5.)),
+                        ..default()
+                    }),
+                Label,
+                SeedText,
+            ));
             // Resume
             parent
                 .spawn((ButtonBundle {
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use crate::arena::seed::WorldSeed;
+use crate::ui::main_menu::{edit_seed_input, SeedEntry};
+
+// Anything above u32::MAX / 10 used to swallow every digit typed after it
+const LARGE_SEED: u32 = 4_000_000_000;
+
+fn seed_app(seed: u32) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins)
+        .add_event::<ReceivedCharacter>()
+        .init_resource::<Input<KeyCode>>()
+        .init_resource::<SeedEntry>()
+        .insert_resource(WorldSeed(seed))
+        .add_systems(Update, edit_seed_input);
+    app
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for char in text.chars() {
+        app.world.send_event(ReceivedCharacter {
+            window: Entity::PLACEHOLDER,
+            char
+        });
+    }
+    app.update();
+}
+
+fn seed(app: &App) -> u32 {
+    app.world.resource::<WorldSeed>().0
+}
+
+#[test]
+fn typing_onto_a_large_seed_starts_a_new_one() {
+    let mut app = seed_app(LARGE_SEED);
+
+    type_text(&mut app, "4");
+    assert_eq!(seed(&app), 4);
+    type_text(&mut app, "2");
+    assert_eq!(seed(&app), 42);
+}
+
+#[test]
+fn rerolled_seed_is_replaced_by_the_next_keypress() {
+    let mut app = seed_app(LARGE_SEED);
+    type_text(&mut app, "12");
+
+    app.world.insert_resource(WorldSeed(LARGE_SEED + 1));
+    type_text(&mut app, "7");
+    assert_eq!(seed(&app), 7);
+}
+
+#[test]
+fn digits_past_u32_max_are_dropped() {
+    let mut app = seed_app(0);
+
+    type_text(&mut app, "99999999999");
+    assert_eq!(seed(&app), 999_999_999);
+}
@@ -1,12 +1,14 @@
 use bevy::prelude::*;
-use crate::ui::{FONT_PATH, MenuButtonAction, NORMAL_BUTTON, PANEL_BACKGROUND};
+use crate::arena::seed::WorldSeed;
+use crate::ui::{FONT_PATH, MenuButtonAction, NORMAL_BUTTON, PANEL_BACKGROUND, seed_label, SeedText};
 
 #[derive(Component)]
 pub struct VictoryUi;
 
 pub fn setup_victory_ui(
     mut commands: Commands,
-    asset_server: Res<AssetServer>
+    asset_server: Res<AssetServer>,
+    seed: Res<WorldSeed>
 ){
     commands
         // Menu Background
@@ -41,6 +43,23 @@ pub fn setup_victory_ui(
                     }),
                 Label,
             ));
+            // Seed text
+            parent.spawn((
+                TextBundle::from_section(
+                    seed_label(&seed),
+                    TextStyle {
+                        font: asset_server.load(FONT_PATH),
+                        font_size: 10.0,
+                        color: Color::WHITE,
+                    },
+                )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(5.)),
+                        ..default()
+                    }),
+                Label,
+                SeedText,
+            ));
             // Play
             parent
                 .spawn((ButtonBundle {
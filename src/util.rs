@@ -1,8 +1,42 @@
+use std::collections::HashSet;
+use std::f32::consts::TAU;
+use std::fs;
 use bevy::prelude::*;
-pub fn intersecting(a_point: Vec3, b_bound: Vec3, radius: f32) -> bool {
-    (a_point.x < b_bound.x + radius && a_point.x > b_bound.x - radius) &&
-        (a_point.y < b_bound.y + radius && a_point.y > b_bound.y - radius) &&
-        (a_point.z < b_bound.z + radius && a_point.z > b_bound.z - radius)
+use bevy_xpbd_3d::prelude::*;
+
+const CONFIG_PATH: &str = "stela-nubo.cfg";
+
+// Reads `key = value` lines from the config file in the working directory
+pub fn config_value(key: &str) -> Option<String> {
+    let config = fs::read_to_string(CONFIG_PATH).ok()?;
+    config.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .find(|(line_key, _)| line_key.trim() == key)
+        .map(|(_, value)| value.trim().to_string())
+}
+
+// First contact of every pair that started touching, contacts otherwise repeat every substep
+pub fn started_contacts(
+    collision_started: &mut EventReader<CollisionStarted>,
+    collisions: &mut EventReader<Collision>
+) -> Vec<Contact> {
+    let mut started: HashSet<(Entity, Entity)> = collision_started.iter()
+        .map(|CollisionStarted(entity1, entity2)| (*entity1, *entity2))
+        .collect();
+    collisions.iter()
+        .filter(|Collision(contact)| started.remove(&(contact.entity1, contact.entity2)))
+        .map(|Collision(contact)| *contact)
+        .collect()
+}
+
+// Uniformly distributed over the unit sphere
+pub fn random_direction(rng: &mut fastrand::Rng) -> Vec3 {
+    let z = rng.f32() * 2.0 - 1.0;
+    let angle = rng.f32() * TAU;
+    let radius = (1.0 - z * z).sqrt();
+    Vec3::new(radius * angle.cos(), radius * angle.sin(), z)
 }
 
 #[derive(Component)]